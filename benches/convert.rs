@@ -0,0 +1,122 @@
+//! 変換エンジンのベンチマーク (標準ライブラリのみ)。
+//!
+//! `cargo bench --bench convert` で実行する。長い入力に対する各APIの
+//! 1回あたりの時間 (中央値) と入力1文字あたりの時間を表示する。
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use chamsae::hangul::{Composer, ConvertOptions, HangulConverter, InputScheme};
+
+/// 1ケースの計測回数。
+const SAMPLES: usize = 30;
+
+/// ベンチマークの入力 (約 `len` 文字のローマ字文)。
+fn sample_input(len: usize) -> String {
+    const SENTENCES: [&str; 4] = [
+        "an nyeong ha se yo  ",
+        "dae han min gug eun min ju gong hwa gug i da  ",
+        "o neul eun nal ssi ga jo a seo gong won e gass seub ni da  ",
+        "hangug eo reul gong bu ha neun geos eun jae mi iss eo yo  ",
+    ];
+    SENTENCES.iter().cycle().flat_map(|s| s.chars()).take(len).collect()
+}
+
+/// `f` を計測し、1回あたりの時間の中央値を返す。
+fn measure(mut f: impl FnMut()) -> Duration {
+    // ウォームアップ。
+    for _ in 0..3 {
+        f();
+    }
+    let mut samples: Vec<Duration> = (0..SAMPLES)
+        .map(|_| {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        })
+        .collect();
+    samples.sort();
+    samples[SAMPLES / 2]
+}
+
+/// 計測結果を表示する。
+fn report(name: &str, chars: usize, time: Duration) {
+    println!(
+        "{:<40} {:>10.3} ms {:>8.1} ns/char",
+        name,
+        time.as_secs_f64() * 1e3,
+        time.as_nanos() as f64 / chars as f64
+    );
+}
+
+fn main() {
+    let options = ConvertOptions::default();
+    let converter = HangulConverter::shared(InputScheme::Romaja, options);
+    let yale = HangulConverter::shared(InputScheme::Yale, options);
+
+    for len in [1_000, 100_000] {
+        let input = sample_input(len);
+        let chars = input.chars().count();
+        println!("--- {} chars ---", chars);
+
+        report("convert", chars, measure(|| {
+            black_box(converter.convert(black_box(&input)));
+        }));
+
+        let mut out = String::with_capacity(input.len() * 2);
+        report("convert_into (reused buffer)", chars, measure(|| {
+            out.clear();
+            converter.convert_into(black_box(&input), &mut out).unwrap();
+            black_box(&out);
+        }));
+
+        report("convert (yale)", chars, measure(|| {
+            black_box(yale.convert(black_box(&input)));
+        }));
+
+        report("convert_with_spans", chars, measure(|| {
+            black_box(converter.convert_with_spans(black_box(&input), None));
+        }));
+
+        report("Composer::push", chars, measure(|| {
+            let mut composer = Composer::with_converter(converter.clone());
+            for c in input.chars() {
+                black_box(composer.push(c));
+            }
+        }));
+
+        let words: Vec<&str> = input.split("  ").collect();
+        report("convert_candidates (per word)", chars, measure(|| {
+            for word in &words {
+                black_box(converter.convert_candidates(black_box(word), 5));
+            }
+        }));
+
+        // TSF はキー入力ごとにバッファ全体の候補を求める。区切りのない1つの音節群も計測する。
+        let unspaced: String = input.chars().filter(|&c| c != ' ').collect();
+        for (name, text) in [("", &input), (" unspaced", &unspaced)] {
+            report(&format!("convert_candidates (whole{})", name), chars, measure(|| {
+                black_box(converter.convert_candidates(black_box(text), 9));
+            }));
+
+            let mut composer = Composer::with_converter(converter.clone());
+            for c in text.chars() {
+                composer.push(c);
+            }
+            report(&format!("Composer::candidates (whole{})", name), chars, measure(|| {
+                black_box(composer.candidates(9));
+            }));
+        }
+    }
+
+    // 変換器の作成 (テーブルからトライを構築する) は入力の長さによらない。
+    println!("--- construction ---");
+    let time = measure(|| {
+        black_box(HangulConverter::with_scheme(InputScheme::Romaja, options));
+    });
+    println!("{:<40} {:>10.3} ms", "HangulConverter::with_scheme", time.as_secs_f64() * 1e3);
+    let time = measure(|| {
+        black_box(HangulConverter::shared(InputScheme::Romaja, options));
+    });
+    println!("{:<40} {:>10.3} ms", "HangulConverter::shared", time.as_secs_f64() * 1e3);
+}
@@ -1,9 +1,19 @@
 //! ハングル変換CLIツール。
 
-use anyhow::Result;
-use clap::Parser;
+use anyhow::{bail, Context, Result};
+use clap::{Parser, Subcommand};
+use chamsae::choseong::{self, ChoseongDict};
 use chamsae::config::Config;
-use chamsae::hangul::HangulConverter;
+use chamsae::diagnostic;
+use chamsae::hanja::HanjaDict;
+use chamsae::hangul::{CasePolicy, ConvertOptions, HangulConverter, InputScheme, SyllableBreak};
+use chamsae::keyboard::KeyboardConverter;
+use chamsae::normalize::{self, OutputForm};
+use chamsae::numeral;
+use chamsae::palette::SymbolPalette;
+use chamsae::spelling::SpellingDict;
+use chamsae::user_dict::UserDict;
+use chamsae::romanize::{self, RomanizeOptions};
 
 /// コマンドライン引数。
 #[derive(Parser, Debug)]
@@ -17,6 +27,82 @@ struct Args {
 	#[arg(short = 'I', long)]
 	interactive: bool,
 
+	/// 入力方式 (romaja, mccune_reischauer, yale, dubeolsik, sebeolsik_390, sebeolsik_final)。
+	/// 省略時は設定ファイルの値、なければ romaja。
+	#[arg(short = 's', long, value_parser = parse_scheme)]
+	scheme: Option<InputScheme>,
+
+	/// 母音が続かない子音を互換字母 (ㅋ, ㅎ など) で出力 (ローマ字入力のみ)。
+	#[arg(long)]
+	compat_jamo: bool,
+
+	/// 音節区切り (space = スペース1つで区切り・2つでスペース、記号1文字 = その記号で区切り)。
+	/// 省略時は設定ファイルの値、なければ space。
+	#[arg(long, value_name = "BREAK", value_parser = parse_syllable_break)]
+	syllable_break: Option<SyllableBreak>,
+
+	/// 古語の字母 (ㆍ, ㅿ, ㆁ, ㆆ など) を結合型字母で出力 (ローマ字入力のみ)。
+	#[arg(long)]
+	archaic: bool,
+
+	/// 出力形式 (nfc = 完成形, nfd = 結合型字母, compat = 互換字母)。
+	/// 省略時は設定ファイルの値、なければ nfc。
+	#[arg(long, value_name = "FORM", value_parser = parse_output_form, conflicts_with = "reverse")]
+	form: Option<OutputForm>,
+
+	/// 入力のハングル文字列を --form の出力形式に変換 (正規化) する。
+	#[arg(long, conflicts_with_all = ["reverse", "candidates"])]
+	normalize: bool,
+
+	/// 大文字の扱い (ignore = 小文字として扱う, shift = 濃音・ㅣ系の二重母音, preserve_uppercase = 大文字の単語は英字のまま)。ローマ字入力のみ。
+	/// 省略時は設定ファイルの値、なければ ignore。
+	#[arg(long, value_name = "POLICY", value_parser = parse_case_policy)]
+	case: Option<CasePolicy>,
+
+	/// 記号の綴りを記号に変換 (<< → 《, .. → ·, \! → ！ など。綴りはテーブルの symbols。ローマ字入力のみ)。
+	#[arg(long)]
+	symbols: bool,
+
+	/// 発音どおりの綴りを標準の綴りに戻して出力 (gamsahamnida → 감사합니다)。
+	/// 単語リストに一致する語がなければ通常の変換結果を出力する。
+	/// 設定ファイルの単語リストとユーザー辞書の語も参照する。
+	#[arg(long, conflicts_with_all = ["reverse", "candidates", "normalize", "spans"])]
+	spelling: bool,
+
+	/// 設定ファイル (chamsae.json) から入力方式・変換オプションを読み込む。解析できない内容や不明な名前はエラーにする。
+	#[arg(short = 'c', long, value_name = "PATH")]
+	config: Option<std::path::PathBuf>,
+
+	/// ローマ字マッピングテーブルファイル (JSON)。
+	#[arg(long, value_name = "PATH")]
+	table: Option<std::path::PathBuf>,
+
+	/// 音節の区切り方が異なる変換候補を最大N件、スコア付きで表示 (ローマ字入力のみ)。
+	#[arg(short = 'n', long, value_name = "N", conflicts_with = "reverse")]
+	candidates: Option<usize>,
+
+	/// 出力の単位 (音節) ごとに入力の文字範囲と由来 (converted, passthrough, user_dict, symbol) を表示 (ローマ字入力のみ)。
+	/// 設定ファイルにユーザー辞書のパスがあれば辞書も参照する。
+	#[arg(long, conflicts_with_all = ["reverse", "candidates", "normalize"])]
+	spans: bool,
+
+	/// 変換結果と、入力の誤りと思われる箇所 (変換できない英字・母音が続かない子音・まれな音節) を位置付きで表示 (ローマ字入力のみ)。
+	/// 1件でもあれば終了コード 1 で終了する。
+	#[arg(long, conflicts_with_all = ["reverse", "candidates", "normalize", "spans", "spelling"])]
+	check: bool,
+
+	/// 逆変換モード (ハングル→ローマ字)。
+	#[arg(short = 'r', long)]
+	reverse: bool,
+
+	/// 逆変換で転写表記 (綴りどおり) を使用。
+	#[arg(long, requires = "reverse")]
+	strict: bool,
+
+	/// 逆変換で音韻変化 (鼻音化・激音化など) を反映しない。
+	#[arg(long, requires = "reverse")]
+	no_assimilation: bool,
+
 	/// 設定ファイルのテンプレートをカレントディレクトリに生成。
 	#[arg(short = 't', long = "template")]
 	template: bool,
@@ -24,6 +110,89 @@ struct Args {
 	/// バージョン表示。
 	#[arg(short = 'v', long = "version")]
 	version: bool,
+
+	#[command(subcommand)]
+	command: Option<Command>,
+}
+
+/// サブコマンド。
+#[derive(Subcommand, Debug)]
+enum Command {
+	/// 数字を韓国語の数詞で読む (漢数詞・固有数詞・数字と単位の混用、または助数詞付き)。
+	Number {
+		/// 数字 (カンマ区切り可)。
+		number: String,
+
+		/// 助数詞 (개, 명, 년, 월 など)。固有数詞をとる助数詞では固有数詞で読む。
+		#[arg(long)]
+		counter: Option<String>,
+	},
+
+	/// ハングルの漢字候補を意味付きで表示する (先頭から一致する長い読みの順)。
+	Hanja {
+		/// ハングル文字列。
+		text: String,
+
+		/// 漢字辞書ファイル (hanja.txt 形式)。標準の漢字辞書に追加する。
+		#[arg(long, value_name = "PATH")]
+		dict: Option<std::path::PathBuf>,
+
+		/// 表示する候補の最大数。
+		#[arg(short = 'n', long, value_name = "N", default_value_t = 9)]
+		limit: usize,
+	},
+
+	/// 漢字 (日本の新字体を含む) をハングルの音にする (語頭は頭音法則を適用)。漢字以外はそのまま。
+	Reading {
+		/// 変換する文字列。省略時は標準入力の各行を変換する。
+		text: Option<String>,
+
+		/// 漢字辞書ファイル (hanja.txt 形式)。単語の読みを標準の漢字辞書に追加する。
+		#[arg(long, value_name = "PATH")]
+		dict: Option<std::path::PathBuf>,
+	},
+
+	/// テンプレートの {キー} を展開し、助詞の表記 (은/는, 이/가, 을/를, 과/와, (으)로) を直前の文字に合う形にする。
+	Josa {
+		/// テンプレート ({name}은/는 {seoul}(으)로 가요)。省略時は標準入力の各行を展開する。
+		template: Option<String>,
+
+		/// ユーザー辞書ファイル (JSON)。{キー} を辞書の値に置き換え、辞書にないキーはローマ字として変換する。
+		#[arg(long, value_name = "PATH")]
+		dict: Option<std::path::PathBuf>,
+	},
+
+	/// 子音だけの入力 (h g ng, ㅎㄱㅇ) の初声で始まる語を候補の順に表示する (初声の ㅇ は ng)。
+	Choseong {
+		/// 子音のローマ字 (スペースで区切ると別の子音) または互換字母。
+		input: String,
+
+		/// ユーザー辞書ファイル (JSON)。値の語を単語リストより先の候補にする。
+		#[arg(long, value_name = "PATH")]
+		dict: Option<std::path::PathBuf>,
+
+		/// 単語リストファイル (1行1語)。標準の単語リストに追加する。
+		#[arg(long, value_name = "PATH")]
+		words: Option<std::path::PathBuf>,
+
+		/// 表示する候補の最大数。
+		#[arg(short = 'n', long, value_name = "N", default_value_t = 9)]
+		limit: usize,
+	},
+
+	/// 記号パレットの候補を表示する (子音は記号表、それ以外は絵文字のショートコードの前方一致)。
+	Symbol {
+		/// 子音 (ㅁ またはローマ字の m) か絵文字のショートコード (:smi)。省略時は記号表の一覧。
+		key: Option<String>,
+
+		/// 絵文字のショートコード表ファイル (JSON)。標準の表に追加・置換する。
+		#[arg(long, value_name = "PATH")]
+		emoji: Option<std::path::PathBuf>,
+
+		/// 表示する候補の最大数。省略時はすべて。
+		#[arg(short = 'n', long, value_name = "N")]
+		limit: Option<usize>,
+	},
 }
 
 fn main() -> Result<()> {
@@ -34,28 +203,259 @@ fn main() -> Result<()> {
 		return Ok(());
 	} else if args.template {
 		generate_template()?;
-	} else if args.interactive {
-		let converter = HangulConverter::new();
-		run_interactive(&converter)?;
-	} else if let Some(input) = args.input {
-		let converter = HangulConverter::new();
-		println!("{}", converter.convert(&input));
+		return Ok(());
+	}
+
+	if let Some(command) = &args.command {
+		return run_command(command);
+	}
+
+	let config = match &args.config {
+		Some(path) => Config::from_json_strict(&std::fs::read_to_string(path)?)
+			.with_context(|| format!("設定ファイルが不正です: {}", path.display()))?,
+		None => Config::default(),
+	};
+	let scheme = args.scheme.unwrap_or(config.input_scheme);
+	let table = args.table.clone().or(config.romaja_table_path.map(Into::into));
+
+	let tables = scheme.romaja_tables().unwrap_or_default();
+	let tables = match &table {
+		Some(path) => {
+			let (tables, conflicts) = tables.extend_file(path)?;
+			for conflict in &conflicts {
+				eprintln!("警告: {}", conflict);
+			}
+			tables
+		}
+		None => tables,
+	};
+	let converter = HangulConverter::with_tables(tables, ConvertOptions {
+		compat_jamo: args.compat_jamo || config.convert_options.compat_jamo,
+		syllable_break: args.syllable_break.unwrap_or(config.convert_options.syllable_break),
+		archaic: args.archaic || config.convert_options.archaic,
+		form: args.form.unwrap_or(config.convert_options.form),
+		case: args.case.unwrap_or(config.convert_options.case),
+		symbols: args.symbols || config.convert_options.symbols,
+	});
+	let dict = config.user_dict_path.as_ref().map(|path| UserDict::load(std::path::Path::new(path)));
+	let spelling = if args.spelling {
+		let mut spelling = SpellingDict::standard();
+		if let Some(path) = &config.spelling_word_list_path {
+			spelling.extend_file(std::path::Path::new(path))?;
+		}
+		if let Some(dict) = &dict {
+			spelling.extend_user_dict(dict);
+		}
+		Some(spelling)
+	} else {
+		None
+	};
+	let keyboard = scheme.layout().map(KeyboardConverter::new);
+	let options = RomanizeOptions {
+		assimilation: !args.no_assimilation,
+		strict: args.strict,
+	};
+	if keyboard.is_some() {
+		let romaja_only = [
+			("--check", args.check),
+			("--candidates", args.candidates.is_some()),
+			("--spans", args.spans),
+			("--case", args.case.is_some()),
+			("--symbols", args.symbols),
+			("--archaic", args.archaic),
+			("--compat-jamo", args.compat_jamo),
+		];
+		if let Some((flag, _)) = romaja_only.iter().find(|(_, used)| *used) {
+			bail!("{} はローマ字入力でのみ使えます", flag);
+		}
+	}
+	let problems = std::cell::Cell::new(false);
+	let convert = |line: &str| {
+		let form = converter.options().form;
+		if args.reverse {
+			romanize::romanize(line, &options)
+		} else if args.normalize {
+			normalize::normalize(line, form)
+		} else if let Some(keyboard) = &keyboard {
+			normalize::normalize(&keyboard.convert(line), form)
+		} else if args.spans {
+			let chars: Vec<char> = line.chars().collect();
+			converter
+				.convert_with_spans(line, dict.as_ref())
+				.iter()
+				.map(|span| {
+					let roman: String = chars[span.chars.clone()].iter().collect();
+					format!("{:>3}..{:<3} {:<8} {}\t{}", span.chars.start, span.chars.end, roman, span.text, span.source.name())
+				})
+				.collect::<Vec<_>>()
+				.join("\n")
+		} else if args.check {
+			let result = diagnostic::check(&converter, line, dict.as_ref());
+			if !result.is_clean() {
+				problems.set(true);
+			}
+			std::iter::once(result.text)
+				.chain(result.diagnostics.iter().map(|d| format!("  {}", d)))
+				.collect::<Vec<_>>()
+				.join("\n")
+		} else if let Some(spelling) = &spelling {
+			spelling
+				.candidates(&converter, line, 1)
+				.into_iter()
+				.next()
+				.unwrap_or_else(|| converter.convert(line))
+		} else if let Some(limit) = args.candidates {
+			let candidates = converter.convert_candidates(line, limit);
+			candidates
+				.iter()
+				.enumerate()
+				.map(|(i, c)| format!("{}. {} ({})", i + 1, c.text, c.score))
+				.collect::<Vec<_>>()
+				.join("\n")
+		} else {
+			converter.convert(line)
+		}
+	};
+
+	if args.interactive {
+		run_interactive(&convert)?;
+	} else if let Some(input) = &args.input {
+		println!("{}", convert(input));
 	} else {
-		let converter = HangulConverter::new();
-		run_stdin(&converter)?;
+		run_stdin(&convert)?;
 	}
 
+	if problems.get() {
+		std::process::exit(1);
+	}
 	Ok(())
 }
 
+/// サブコマンドを実行する。
+fn run_command(command: &Command) -> Result<()> {
+	match command {
+		Command::Number { number, counter } => {
+			let n = numeral::parse(number).with_context(|| format!("数字ではありません: {}", number))?;
+			match counter {
+				Some(counter) => println!("{}", numeral::with_counter(n, counter)),
+				None => {
+					println!("漢数詞: {}", numeral::sino(n));
+					if let Some(native) = numeral::native(n) {
+						println!("固有数詞: {}", native);
+					}
+					println!("混用: {}", numeral::mixed(n));
+				}
+			}
+		}
+		Command::Hanja { text, dict, limit } => {
+			let hanja = load_hanja(dict.as_deref())?;
+			for (i, c) in hanja.candidates(text, *limit).iter().enumerate() {
+				println!("{}", format!("{}. {}\t({}) {}", i + 1, c.text, c.key, c.meaning).trim_end());
+			}
+		}
+		Command::Symbol { key, emoji, limit } => {
+			let mut palette = SymbolPalette::standard();
+			if let Some(path) = emoji {
+				palette.extend_emoji_file(path)?;
+			}
+			let Some(key) = key else {
+				for group in palette.groups() {
+					let symbols: String = group.symbols.iter().collect();
+					println!("{} {}: {}", group.consonant, group.name, symbols);
+				}
+				return Ok(());
+			};
+			// ローマ字の子音 (m, kk) は記号表の子音として扱う。
+			let key = HangulConverter::new().consonant(key).map_or(key.clone(), String::from);
+			for (i, c) in palette.candidates(&key, limit.unwrap_or(usize::MAX)).iter().enumerate() {
+				println!("{}. {}", i + 1, c.label);
+			}
+		}
+		Command::Choseong { input, dict, words, limit } => {
+			let converter = HangulConverter::new();
+			let Some(pattern) = choseong::pattern(&converter, input) else {
+				bail!("子音だけの入力ではありません: {}", input);
+			};
+			let mut choseong = ChoseongDict::standard();
+			if let Some(path) = words {
+				choseong.extend_file(path)?;
+			}
+			if let Some(path) = dict {
+				choseong.extend_user_dict(&UserDict::load(path));
+			}
+			let initials: String = pattern.iter().map(|cho| cho.to_compat()).collect();
+			println!("{}", initials);
+			for (i, word) in choseong.search(&pattern, *limit).iter().enumerate() {
+				println!("{}. {}", i + 1, word);
+			}
+		}
+		Command::Josa { template, dict } => {
+			let dict = dict.as_deref().map_or_else(UserDict::empty, UserDict::load);
+			let converter = HangulConverter::new();
+			let expand = |line: &str| dict.expand_template(line, &converter);
+			match template {
+				Some(template) => println!("{}", expand(template)),
+				None => run_stdin(&expand)?,
+			}
+		}
+		Command::Reading { text, dict } => {
+			let hanja = load_hanja(dict.as_deref())?;
+			match text {
+				Some(text) => println!("{}", hanja.to_hangul(text)),
+				None => run_stdin(&|line| hanja.to_hangul(line))?,
+			}
+		}
+	}
+	Ok(())
+}
+
+/// 標準の漢字辞書に、指定があれば漢字辞書ファイルを追加して読み込む。
+fn load_hanja(path: Option<&std::path::Path>) -> Result<HanjaDict> {
+	let mut hanja = HanjaDict::standard();
+	if let Some(path) = path {
+		hanja.extend_file(path)?;
+	}
+	Ok(hanja)
+}
+
+/// 入力方式名を解析する。
+fn parse_scheme(name: &str) -> Result<InputScheme, String> {
+	InputScheme::from_name(name).ok_or_else(|| {
+		let names: Vec<&str> = InputScheme::ALL.iter().map(|s| s.name()).collect();
+		format!("不明な入力方式: {} (指定可能: {})", name, names.join(", "))
+	})
+}
+
+/// 音節区切りを解析する。
+fn parse_syllable_break(name: &str) -> Result<SyllableBreak, String> {
+	SyllableBreak::from_name(name)
+		.ok_or_else(|| format!("不明な音節区切り: {} (指定可能: space, または ' - . などの記号1文字)", name))
+}
+
+/// 出力形式を解析する。
+fn parse_output_form(name: &str) -> Result<OutputForm, String> {
+	OutputForm::from_name(name).ok_or_else(|| {
+		let names: Vec<&str> = OutputForm::ALL.iter().map(|f| f.name()).collect();
+		format!("不明な出力形式: {} (指定可能: {})", name, names.join(", "))
+	})
+}
+
+/// 大文字の扱いを解析する。
+fn parse_case_policy(name: &str) -> Result<CasePolicy, String> {
+	CasePolicy::from_name(name).ok_or_else(|| {
+		let names: Vec<&str> = CasePolicy::ALL.iter().map(|c| c.name()).collect();
+		format!("不明な大文字の扱い: {} (指定可能: {})", name, names.join(", "))
+	})
+}
+
 /// 標準入力から読み込んで変換する。
-fn run_stdin(converter: &HangulConverter) -> Result<()> {
+fn run_stdin(convert: &dyn Fn(&str) -> String) -> Result<()> {
 	use std::io::{self, BufRead};
 
 	let stdin = io::stdin();
 	for line in stdin.lock().lines() {
 		let line = line?;
-		println!("{}", converter.convert(&line));
+		println!("{}", convert(&line));
 	}
 
 	Ok(())
@@ -74,7 +474,7 @@ fn generate_template() -> Result<()> {
 }
 
 /// インタラクティブモード。
-fn run_interactive(converter: &HangulConverter) -> Result<()> {
+fn run_interactive(convert: &dyn Fn(&str) -> String) -> Result<()> {
 	use std::io::{self, BufRead, Write};
 
 	println!("ハングル変換 (exitで終了)");
@@ -93,7 +493,7 @@ fn run_interactive(converter: &HangulConverter) -> Result<()> {
 			break;
 		}
 
-		println!("  → {}", converter.convert(input));
+		println!("  → {}", convert(input));
 	}
 
 	Ok(())
@@ -62,6 +62,9 @@ fn run_settings() -> anyhow::Result<()> {
         #[serde(default)]
         #[serde(skip_serializing_if = "Option::is_none")]
         user_dict_path: Option<String>,
+        /// 画面で編集しない項目 (入力方式など)。保存時にそのまま書き戻す。
+        #[serde(flatten)]
+        extra: serde_json::Map<String, serde_json::Value>,
     }
 
     #[derive(serde::Serialize, serde::Deserialize)]
@@ -98,6 +101,7 @@ fn run_settings() -> anyhow::Result<()> {
                 },
                 languages: LanguagesJson::default(),
                 user_dict_path: None,
+                extra: serde_json::Map::new(),
             }
         }
     }
@@ -151,6 +155,7 @@ fn run_settings() -> anyhow::Result<()> {
     struct AppState {
         controls: Controls,
         config_path: std::path::PathBuf,
+        extra: serde_json::Map<String, serde_json::Value>,
     }
 
     struct Controls {
@@ -211,6 +216,7 @@ fn run_settings() -> anyhow::Result<()> {
                                 } else {
                                     Some(dict_path)
                                 },
+                                extra: state.extra.clone(),
                             };
 
                             // JSONに保存。
@@ -529,6 +535,7 @@ fn run_settings() -> anyhow::Result<()> {
         create_button(hwnd, hinstance.into(), IDC_SAVE, "保存", 210, 300, 85, 30);
         create_button(hwnd, hinstance.into(), IDC_CANCEL, "キャンセル", 303, 300, 85, 30);
 
+        let extra = cfg.extra.clone();
         drop(cfg);
 
         // AppStateを作成しグローバルに設定。
@@ -543,6 +550,7 @@ fn run_settings() -> anyhow::Result<()> {
                 dict_edit,
             },
             config_path: (*config_path).clone(),
+            extra,
         });
         APP_STATE.store(Box::into_raw(state), std::sync::atomic::Ordering::Relaxed);
 
@@ -0,0 +1,224 @@
+//! 初声 (초성) 検索モジュール。
+//!
+//! 語の各音節の初声だけを入力して (ㅎㄱㅇ → 한국어)、単語リストとユーザー辞書から語を探す。
+//!
+//! - 入力: 子音だけのローマ字 (`h g ng`, `hgng`) または互換字母 (`ㅎㄱㅇ`)。
+//!   ローマ字の子音はスペースで区切った中で、ローマ字表の初声の綴りの最長一致で区切る。
+//!   初声の ㅇ にはローマ字の綴りがないため、終声と同じ `ng` で入力する
+//! - 候補の順: 初声の数が同じ語、ユーザー辞書の語、短い語、単語リストで前にある語の順
+//!
+//! 単語リストは1行1語のテキスト (`#` で始まる行と空行は無視)。語の中のスペースは無視して初声を並べる。
+
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+
+use crate::hangul::HangulConverter;
+use crate::jamo::{self, Cho};
+use crate::user_dict::UserDict;
+
+/// 標準の単語リスト。
+const STANDARD_WORDS: &str = include_str!("data/choseong_words.txt");
+
+/// 初声 ㅇ の綴り。
+const IEUNG_KEY: &str = "ng";
+
+/// 初声 ㅇ のインデックス。
+const CHO_IEUNG: u32 = 11;
+
+/// 初声検索の対象の語。
+struct Entry {
+    /// 語。
+    word: String,
+    /// 各音節の初声。
+    initials: Vec<Cho>,
+    /// ユーザー辞書の語か。
+    user: bool,
+}
+
+/// 初声で語を探す辞書。
+pub struct ChoseongDict {
+    /// 語 (追加順)。
+    entries: Vec<Entry>,
+    /// 追加済みの語。
+    words: HashSet<String>,
+}
+
+impl ChoseongDict {
+    /// 空の辞書を作成する。
+    pub fn empty() -> Self {
+        Self { entries: Vec::new(), words: HashSet::new() }
+    }
+
+    /// 標準の単語リスト (よく使う語) の辞書を作成する。
+    pub fn standard() -> Self {
+        let mut dict = Self::empty();
+        dict.extend_words(STANDARD_WORDS);
+        dict
+    }
+
+    /// 語を追加する。ハングルとスペース以外の文字を含む語と、追加済みの語は無視する。
+    pub fn add_word(&mut self, word: &str) {
+        self.add(word, false);
+    }
+
+    /// 語を追加する (`user` はユーザー辞書の語か)。
+    fn add(&mut self, word: &str, user: bool) {
+        let initials: Option<Vec<Cho>> = word
+            .chars()
+            .filter(|&c| c != ' ')
+            .map(|c| jamo::decompose(c).map(|(cho, _, _)| cho))
+            .collect();
+        let Some(initials) = initials.filter(|i| !i.is_empty()) else {
+            return;
+        };
+        if self.words.insert(word.to_string()) {
+            self.entries.push(Entry { word: word.to_string(), initials, user });
+        }
+    }
+
+    /// 単語リストのテキスト (1行1語、`#` で始まる行と空行は無視) の語を追加する。
+    pub fn extend_words(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if !line.starts_with('#') {
+                self.add_word(line);
+            }
+        }
+    }
+
+    /// 単語リストファイルの語を追加する。
+    pub fn extend_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("単語リストを読み込めません: {}", path.display()))?;
+        self.extend_words(&content);
+        Ok(())
+    }
+
+    /// ユーザー辞書の値を語として追加する (テンプレートなどハングル以外を含む値は無視)。
+    ///
+    /// 単語リストに同じ語があっても、ユーザー辞書の語として先の候補にする。
+    pub fn extend_user_dict(&mut self, dict: &UserDict) {
+        let mut values: Vec<&str> = dict.values().collect();
+        values.sort_unstable();
+        for value in values {
+            self.entries.retain(|e| e.word != value);
+            self.words.remove(value);
+            self.add(value, true);
+        }
+    }
+
+    /// 初声の並びで始まる語を、候補の順に最大 `limit` 件返す。
+    pub fn search(&self, pattern: &[Cho], limit: usize) -> Vec<String> {
+        if pattern.is_empty() {
+            return Vec::new();
+        }
+        let mut found: Vec<(usize, &Entry)> = self
+            .entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.initials.starts_with(pattern))
+            .collect();
+        found.sort_by_key(|(i, e)| (e.initials.len() != pattern.len(), !e.user, e.initials.len(), *i));
+        found.into_iter().take(limit).map(|(_, e)| e.word.clone()).collect()
+    }
+
+    /// 子音だけの入力 (ローマ字・互換字母) の初声で始まる語を、候補の順に最大 `limit` 件返す。
+    ///
+    /// 子音以外を含む入力では空。
+    pub fn candidates(&self, converter: &HangulConverter, input: &str, limit: usize) -> Vec<String> {
+        pattern(converter, input).map_or_else(Vec::new, |pattern| self.search(&pattern, limit))
+    }
+}
+
+impl Default for ChoseongDict {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// 子音だけの入力を初声の並びにする (`h g ng`, `hgng`, `ㅎㄱㅇ` → ㅎㄱㅇ)。
+///
+/// ローマ字の子音はスペースで区切った中で、ローマ字表の初声の綴り (と ㅇ の `ng`) の最長一致で区切る
+/// (`kk` → ㄲ, `k k` → ㅋㅋ)。子音以外を含む入力や空の入力はNone。
+pub fn pattern(converter: &HangulConverter, input: &str) -> Option<Vec<Cho>> {
+    let mut initials = Vec::new();
+    for token in input.split_whitespace() {
+        let chars: Vec<char> = token.chars().collect();
+        let mut pos = 0;
+        while pos < chars.len() {
+            let (cho, len) = (1..=chars.len() - pos).rev().find_map(|len| {
+                let key: String = chars[pos..pos + len].iter().collect();
+                let cho = match Cho::from_compat(chars[pos]) {
+                    Some(cho) if len == 1 => Some(cho),
+                    _ if key.eq_ignore_ascii_case(IEUNG_KEY) => Cho::new(CHO_IEUNG),
+                    _ => converter.consonant(&key).and_then(Cho::from_compat),
+                };
+                cho.map(|cho| (cho, len))
+            })?;
+            initials.push(cho);
+            pos += len;
+        }
+    }
+    (!initials.is_empty()).then_some(initials)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn initials(text: &str) -> Vec<Cho> {
+        text.chars().map(|c| Cho::from_compat(c).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_pattern() {
+        let c = HangulConverter::new();
+        assert_eq!(pattern(&c, "h g ng"), Some(initials("ㅎㄱㅇ")));
+        assert_eq!(pattern(&c, "hgng"), Some(initials("ㅎㄱㅇ")));
+        assert_eq!(pattern(&c, "ㅎㄱㅇ"), Some(initials("ㅎㄱㅇ")));
+        assert_eq!(pattern(&c, "SR"), Some(initials("ㅅㄹ")));
+        // 最長一致 (kk → ㄲ, ch → ㅊ)。スペースで区切れば別の子音。
+        assert_eq!(pattern(&c, "kkch"), Some(initials("ㄲㅊ")));
+        assert_eq!(pattern(&c, "k k"), Some(initials("ㅋㅋ")));
+        assert_eq!(pattern(&c, "han"), None);
+        assert_eq!(pattern(&c, "f"), None);
+        assert_eq!(pattern(&c, " "), None);
+    }
+
+    #[test]
+    fn test_search() {
+        let dict = ChoseongDict::standard();
+        let c = HangulConverter::new();
+        assert_eq!(dict.candidates(&c, "h g ng", 1), vec!["한국어"]);
+        // 初声の数が同じ語が単語リストの順に先、続けて短い語。
+        assert_eq!(dict.candidates(&c, "hg", 5), vec!["학교", "한국", "한글", "한국어", "한국인"]);
+        assert_eq!(dict.candidates(&c, "ㄱㅅㅎㄴㄷ", 3), vec!["감사합니다"]);
+        assert_eq!(dict.candidates(&c, "ㅈㅂㅌ", 1), vec!["잘 부탁드립니다"]);
+        assert!(dict.candidates(&c, "annyeong", 5).is_empty());
+    }
+
+    #[test]
+    fn test_user_dict() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user_dict.json");
+        std::fs::write(
+            &path,
+            r#"{ "entries": { "hg": "한강", "seoul": "서울", "intro": "{name}은/는" } }"#,
+        )
+        .unwrap();
+        let mut dict = ChoseongDict::standard();
+        dict.extend_user_dict(&UserDict::load(&path));
+        let c = HangulConverter::new();
+        // ユーザー辞書の語が単語リストの語より先。
+        assert_eq!(dict.candidates(&c, "hg", 3), vec!["한강", "학교", "한국"]);
+        assert_eq!(dict.candidates(&c, "s ng", 1), vec!["서울"]);
+    }
+
+    #[test]
+    fn test_extend_words() {
+        let mut dict = ChoseongDict::empty();
+        dict.extend_words("# コメント\n\n참새\nabc\n참새\n");
+        assert_eq!(dict.search(&initials("ㅊㅅ"), 5), vec!["참새"]);
+        assert!(dict.search(&[], 5).is_empty());
+    }
+}
@@ -5,9 +5,13 @@
 //! ファイルが存在しない場合はデフォルト設定で新規作成する。
 //! パース失敗時はデフォルト値にフォールバックする。
 
+use anyhow::{anyhow, bail, Context, Result};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
 
+use crate::hangul::{CasePolicy, ConvertOptions, InputScheme, SyllableBreak};
+use crate::normalize::OutputForm;
+
 /// トグルキー設定。
 pub struct ToggleKey {
     /// 仮想キーコード。
@@ -34,6 +38,24 @@ pub struct Config {
     pub languages: Languages,
     /// ユーザー辞書ファイルパス。
     pub user_dict_path: Option<String>,
+    /// 入力方式。
+    pub input_scheme: InputScheme,
+    /// ローマ字変換オプション。
+    pub convert_options: ConvertOptions,
+    /// ローマ字マッピングテーブルファイルパス。
+    pub romaja_table_path: Option<String>,
+    /// 発音どおりの綴りから標準の綴りの候補を出すか。
+    pub spelling_candidates: bool,
+    /// 標準の綴りの候補に使う単語リストファイルパス (標準の単語リストに追加)。
+    pub spelling_word_list_path: Option<String>,
+    /// 子音だけの入力で初声検索の候補 (ㅎㄱㅇ → 한국어) を出すか。
+    pub choseong_search: bool,
+    /// 初声検索に使う単語リストファイルパス (標準の単語リストに追加)。
+    pub choseong_word_list_path: Option<String>,
+    /// 漢字辞書ファイルパス (hanja.txt 形式、標準の漢字辞書に追加)。
+    pub hanja_dict_path: Option<String>,
+    /// 絵文字のショートコード表ファイルパス (JSON、標準の表に追加・置換)。
+    pub emoji_table_path: Option<String>,
 }
 
 /// JSON設定ファイルのトグルキー定義。
@@ -69,6 +91,50 @@ pub(crate) struct ConfigJson {
     languages: LanguagesJson,
     #[serde(default)]
     user_dict_path: Option<String>,
+    #[serde(default = "default_input_scheme")]
+    input_scheme: String,
+    #[serde(default)]
+    compat_jamo: bool,
+    #[serde(default = "default_syllable_break")]
+    syllable_break: String,
+    #[serde(default)]
+    archaic: bool,
+    #[serde(default = "default_output_form")]
+    output_form: String,
+    #[serde(default = "default_case_policy")]
+    case_policy: String,
+    #[serde(default)]
+    symbol_mode: bool,
+    #[serde(default)]
+    romaja_table_path: Option<String>,
+    #[serde(default)]
+    spelling_candidates: bool,
+    #[serde(default)]
+    spelling_word_list_path: Option<String>,
+    #[serde(default)]
+    choseong_search: bool,
+    #[serde(default)]
+    choseong_word_list_path: Option<String>,
+    #[serde(default)]
+    hanja_dict_path: Option<String>,
+    #[serde(default)]
+    emoji_table_path: Option<String>,
+}
+
+fn default_input_scheme() -> String {
+    InputScheme::default().name().to_string()
+}
+
+fn default_syllable_break() -> String {
+    SyllableBreak::default().name()
+}
+
+fn default_output_form() -> String {
+    OutputForm::default().name().to_string()
+}
+
+fn default_case_policy() -> String {
+    CasePolicy::default().name().to_string()
 }
 
 /// キー名文字列を仮想キーコードに変換する。
@@ -96,6 +162,18 @@ fn key_name_to_vk(name: &str) -> Option<u32> {
     }
 }
 
+/// 名前から得た値を返す。不明な名前は `strict` ならエラー、そうでなければ警告してデフォルト値を返す。
+fn known_or_default<T: Default>(value: Option<T>, field: &str, name: &str, strict: bool) -> Result<T> {
+    match value {
+        Some(value) => Ok(value),
+        None if strict => bail!("Unknown {}: {}", field, name),
+        None => {
+            log::warn!("Unknown {}: {}", field, name);
+            Ok(T::default())
+        }
+    }
+}
+
 impl Default for ConfigJson {
     fn default() -> Self {
         Self {
@@ -110,13 +188,27 @@ impl Default for ConfigJson {
                 korean: false,
             },
             user_dict_path: None,
+            input_scheme: default_input_scheme(),
+            compat_jamo: false,
+            syllable_break: default_syllable_break(),
+            archaic: false,
+            output_form: default_output_form(),
+            case_policy: default_case_policy(),
+            symbol_mode: false,
+            romaja_table_path: None,
+            spelling_candidates: false,
+            spelling_word_list_path: None,
+            choseong_search: false,
+            choseong_word_list_path: None,
+            hanja_dict_path: None,
+            emoji_table_path: None,
         }
     }
 }
 
-impl Config {
+impl Default for Config {
     /// デフォルト設定を返す (Shift+Space、日本語有効、韓国語無効)。
-    pub fn default() -> Self {
+    fn default() -> Self {
         Self {
             toggle_key: ToggleKey {
                 vk: 0x20, // VK_SPACE
@@ -129,9 +221,20 @@ impl Config {
                 korean: false,
             },
             user_dict_path: None,
+            input_scheme: InputScheme::default(),
+            convert_options: ConvertOptions::default(),
+            romaja_table_path: None,
+            spelling_candidates: false,
+            spelling_word_list_path: None,
+            choseong_search: false,
+            choseong_word_list_path: None,
+            hanja_dict_path: None,
+            emoji_table_path: None,
         }
     }
+}
 
+impl Config {
     /// 設定ファイルを読み込む。
     ///
     /// `dll_dir` 内の `chamsae.json` を読み込む。
@@ -158,29 +261,48 @@ impl Config {
             }
         };
 
-        let json: ConfigJson = match serde_json::from_str(&content) {
-            Ok(j) => j,
+        Self::from_json(&content)
+    }
+
+    /// JSON文字列から設定を作成する。
+    ///
+    /// パース失敗やキー名不正の場合はデフォルト値を返す。
+    pub fn from_json(content: &str) -> Self {
+        match Self::parse_json(content, false) {
+            Ok(config) => config,
             Err(e) => {
-                log::warn!("Failed to parse config JSON: {}", e);
-                return Self::default();
+                log::warn!("{:#}", e);
+                Self::default()
             }
-        };
+        }
+    }
 
-        let vk = match key_name_to_vk(&json.toggle_key.key) {
-            Some(v) => v,
-            None => {
-                log::warn!("Unknown key name: {}", json.toggle_key.key);
-                return Self::default();
-            }
-        };
+    /// JSON文字列から設定を作成する。パース失敗や不明な名前 (キー名・入力方式など) はエラーを返す。
+    pub fn from_json_strict(content: &str) -> Result<Self> {
+        Self::parse_json(content, true)
+    }
+
+    /// JSON文字列から設定を作成する。
+    ///
+    /// `strict` がfalseなら、入力方式などの不明な名前は警告してその項目だけデフォルト値にする。
+    fn parse_json(content: &str, strict: bool) -> Result<Self> {
+        let json: ConfigJson = serde_json::from_str(content).context("Failed to parse config JSON")?;
+
+        let vk = key_name_to_vk(&json.toggle_key.key)
+            .ok_or_else(|| anyhow!("Unknown key name: {}", json.toggle_key.key))?;
+        let input_scheme = known_or_default(InputScheme::from_name(&json.input_scheme), "input_scheme", &json.input_scheme, strict)?;
+        let syllable_break = known_or_default(SyllableBreak::from_name(&json.syllable_break), "syllable_break", &json.syllable_break, strict)?;
+        let form = known_or_default(OutputForm::from_name(&json.output_form), "output_form", &json.output_form, strict)?;
+        let case = known_or_default(CasePolicy::from_name(&json.case_policy), "case_policy", &json.case_policy, strict)?;
 
         log::info!(
-            "Config loaded: toggle={}(0x{:02X}) shift={} ctrl={} alt={}",
+            "Config loaded: toggle={}(0x{:02X}) shift={} ctrl={} alt={} scheme={}",
             json.toggle_key.key, vk,
-            json.toggle_key.shift, json.toggle_key.ctrl, json.toggle_key.alt
+            json.toggle_key.shift, json.toggle_key.ctrl, json.toggle_key.alt,
+            input_scheme.name()
         );
 
-        Self {
+        Ok(Self {
             toggle_key: ToggleKey {
                 vk,
                 shift: json.toggle_key.shift,
@@ -192,7 +314,23 @@ impl Config {
                 korean: json.languages.korean,
             },
             user_dict_path: json.user_dict_path,
-        }
+            input_scheme,
+            convert_options: ConvertOptions {
+                compat_jamo: json.compat_jamo,
+                syllable_break,
+                archaic: json.archaic,
+                form,
+                case,
+                symbols: json.symbol_mode,
+            },
+            romaja_table_path: json.romaja_table_path,
+            spelling_candidates: json.spelling_candidates,
+            spelling_word_list_path: json.spelling_word_list_path,
+            choseong_search: json.choseong_search,
+            choseong_word_list_path: json.choseong_word_list_path,
+            hanja_dict_path: json.hanja_dict_path,
+            emoji_table_path: json.emoji_table_path,
+        })
     }
 
     /// 設定ディレクトリから設定を読み込む。
@@ -252,6 +390,11 @@ mod tests {
     use super::*;
     use std::fs;
 
+    /// 切り替えキーだけの基本設定に `fields` (JSONのメンバー) を加えた設定ファイルの内容を返す。
+    fn config_json(fields: &str) -> String {
+        format!(r#"{{ "toggle_key": {{ "key": "Space", "shift": true, "ctrl": false, "alt": false }}, {} }}"#, fields)
+    }
+
     #[test]
     fn test_default_config() {
         let config = Config::default();
@@ -261,6 +404,9 @@ mod tests {
         assert!(!config.toggle_key.alt);
         assert!(config.languages.japanese);
         assert!(!config.languages.korean);
+        assert_eq!(config.input_scheme, InputScheme::Romaja);
+        assert!(!config.convert_options.compat_jamo);
+        assert_eq!(config.convert_options.syllable_break, SyllableBreak::Space);
     }
 
     #[test]
@@ -331,6 +477,171 @@ mod tests {
         let config = Config::load(dir.path());
         assert!(config.languages.japanese);
         assert!(!config.languages.korean);
+        assert_eq!(config.input_scheme, InputScheme::Romaja);
+    }
+
+    #[test]
+    fn test_load_input_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""input_scheme": "dubeolsik""#);
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.input_scheme, InputScheme::Dubeolsik);
+    }
+
+    #[test]
+    fn test_load_sebeolsik_input_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""input_scheme": "sebeolsik_final""#);
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.input_scheme, InputScheme::SebeolsikFinal);
+    }
+
+    #[test]
+    fn test_load_mccune_reischauer_input_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""input_scheme": "mccune_reischauer""#);
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.input_scheme, InputScheme::McCuneReischauer);
+    }
+
+    #[test]
+    fn test_load_compat_jamo() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""compat_jamo": true"#);
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert!(config.convert_options.compat_jamo);
+    }
+
+    #[test]
+    fn test_load_archaic() {
+        let config = Config::from_json(&config_json(r#""archaic": true"#));
+        assert!(config.convert_options.archaic);
+        assert!(!Config::default().convert_options.archaic);
+    }
+
+    #[test]
+    fn test_load_symbol_mode() {
+        let config = Config::from_json(&config_json(r#""symbol_mode": true"#));
+        assert!(config.convert_options.symbols);
+        assert!(!Config::default().convert_options.symbols);
+    }
+
+    #[test]
+    fn test_load_output_form() {
+        let json = config_json(r#""output_form": "nfd""#);
+        let config = Config::from_json(&json);
+        assert_eq!(config.convert_options.form, OutputForm::Nfd);
+
+        let config = Config::from_json(&json.replace("nfd", "nfkc"));
+        assert_eq!(config.convert_options.form, OutputForm::Nfc);
+    }
+
+    #[test]
+    fn test_load_case_policy() {
+        let json = config_json(r#""case_policy": "preserve_uppercase""#);
+        let config = Config::from_json(&json);
+        assert_eq!(config.convert_options.case, CasePolicy::PreserveUppercase);
+
+        let config = Config::from_json(&json.replace("preserve_uppercase", "upper"));
+        assert_eq!(config.convert_options.case, CasePolicy::Ignore);
+    }
+
+    #[test]
+    fn test_load_syllable_break() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""syllable_break": "'""#);
+        fs::write(dir.path().join("chamsae.json"), &json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.convert_options.syllable_break, SyllableBreak::Char('\''));
+
+        let config = Config::from_json(&json.replace("\"'\"", "\"abc\""));
+        assert_eq!(config.convert_options.syllable_break, SyllableBreak::Space);
+    }
+
+    #[test]
+    fn test_load_romaja_table_path() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = config_json(r#""romaja_table_path": "C:\\tables\\team.json""#);
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        let config = Config::load(dir.path());
+        assert_eq!(config.romaja_table_path.as_deref(), Some("C:\\tables\\team.json"));
+    }
+
+    #[test]
+    fn test_load_spelling_candidates() {
+        let json = config_json(r#""spelling_candidates": true, "spelling_word_list_path": "C:\\words\\team.txt""#);
+        let config = Config::from_json(&json);
+        assert!(config.spelling_candidates);
+        assert_eq!(config.spelling_word_list_path.as_deref(), Some("C:\\words\\team.txt"));
+
+        let config = Config::default();
+        assert!(!config.spelling_candidates);
+        assert_eq!(config.spelling_word_list_path, None);
+    }
+
+    #[test]
+    fn test_load_choseong_search() {
+        let json = config_json(r#""choseong_search": true, "choseong_word_list_path": "C:\\words\\team.txt""#);
+        let config = Config::from_json(&json);
+        assert!(config.choseong_search);
+        assert_eq!(config.choseong_word_list_path.as_deref(), Some("C:\\words\\team.txt"));
+
+        let config = Config::default();
+        assert!(!config.choseong_search);
+        assert_eq!(config.choseong_word_list_path, None);
+    }
+
+    #[test]
+    fn test_load_hanja_dict_path() {
+        let json = config_json(r#""hanja_dict_path": "C:\\dict\\hanja.txt""#);
+        let config = Config::from_json(&json);
+        assert_eq!(config.hanja_dict_path.as_deref(), Some("C:\\dict\\hanja.txt"));
+        assert_eq!(Config::default().hanja_dict_path, None);
+    }
+
+    #[test]
+    fn test_load_emoji_table_path() {
+        let json = config_json(r#""emoji_table_path": "C:\\dict\\emoji.json""#);
+        let config = Config::from_json(&json);
+        assert_eq!(config.emoji_table_path.as_deref(), Some("C:\\dict\\emoji.json"));
+        assert_eq!(Config::default().emoji_table_path, None);
+    }
+
+    #[test]
+    fn test_load_unknown_input_scheme_fallback() {
+        let dir = tempfile::tempdir().unwrap();
+        let json = r#"{
+            "toggle_key": { "key": "S", "shift": false, "ctrl": false, "alt": true },
+            "input_scheme": "unknown"
+        }"#;
+        fs::write(dir.path().join("chamsae.json"), json).unwrap();
+
+        // 入力方式のみデフォルトに戻し、他の設定は維持する。
+        let config = Config::load(dir.path());
+        assert_eq!(config.input_scheme, InputScheme::Romaja);
+        assert_eq!(config.toggle_key.vk, 0x53);
+    }
+
+    #[test]
+    fn test_from_json_strict() {
+        let config = Config::from_json_strict(&config_json(r#""input_scheme": "dubeolsik""#)).unwrap();
+        assert_eq!(config.input_scheme, InputScheme::Dubeolsik);
+
+        assert!(Config::from_json_strict("not json").is_err());
+        assert!(Config::from_json_strict(r#"{ "toggle_key": { "key": "InvalidKey", "shift": false, "ctrl": false, "alt": true } }"#).is_err());
+        let err = Config::from_json_strict(&config_json(r#""input_scheme": "unknown""#)).err().unwrap();
+        assert_eq!(err.to_string(), "Unknown input_scheme: unknown");
+        assert!(Config::from_json_strict(&config_json(r#""case_policy": "upper""#)).is_err());
     }
 
     #[test]
@@ -354,6 +665,7 @@ mod tests {
         assert!(json.toggle_key.shift);
         assert!(json.languages.japanese);
         assert!(!json.languages.korean);
+        assert_eq!(json.input_scheme, "romaja");
     }
 
     #[test]
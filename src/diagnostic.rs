@@ -0,0 +1,245 @@
+//! 変換の診断モジュール。
+//!
+//! ローマ字の変換結果に、入力の誤りと思われる箇所を位置付きで報告する。
+//!
+//! - 変換できない英字: ローマ字表にない英字 (f, v, z, q, x など)
+//! - 母音が続かない子音: 音節にならずに英字のまま残った子音 (hangk の k)
+//! - まれな音節: KS X 1001 の 2350 音節にない音節と、現代の文章でほとんど使わない
+//!   初声と中声の組み合わせ (갸, 뎨 など)。母音の打ち間違いで生じやすい
+//!
+//! 変換結果の単位 ([`Span`]) の由来と文字から判定するため、ローマ字入力でのみ使える。
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::hangul::{CasePolicy, HangulConverter, Span, SpanSource};
+use crate::jamo;
+use crate::user_dict::UserDict;
+
+/// KS X 1001 の完成形ハングル 2350 音節。
+const KSX1001_SYLLABLES: &str = include_str!("data/ksx1001_syllables.txt");
+
+/// 中声 ㅑ, ㅒ, ㅖ のインデックス。
+const JUNG_YA: u32 = 2;
+const JUNG_YAE: u32 = 3;
+const JUNG_YE: u32 = 7;
+
+/// 中声 ㅑ の前でまれな初声 (ㄱ, ㄲ, ㄷ, ㄸ, ㅁ, ㅂ, ㅃ, ㅋ, ㅌ, ㅍ)。
+const RARE_BEFORE_YA: [u32; 10] = [0, 1, 3, 4, 6, 7, 8, 15, 16, 17];
+/// 中声 ㅒ の前でまれな初声 (걔 を除き ㅑ と同じ)。
+const RARE_BEFORE_YAE: [u32; 9] = [1, 3, 4, 6, 7, 8, 15, 16, 17];
+/// 中声 ㅖ の前でまれな初声 (ㄴ, ㄷ, ㄸ, ㅁ, ㅂ, ㅃ, ㅈ, ㅉ, ㅊ, ㅋ, ㅌ)。
+const RARE_BEFORE_YE: [u32; 11] = [2, 3, 4, 6, 7, 8, 12, 13, 14, 15, 16];
+
+/// 診断の種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagnosticKind {
+    /// ローマ字表にない英字 (f, v, z, q, x など)。
+    UnmappedLetter,
+    /// 母音が続かず英字のまま残った子音。
+    OrphanConsonant,
+    /// まれな音節 (갸 など)。
+    UnusualSyllable,
+}
+
+impl DiagnosticKind {
+    /// 種類の名前を返す。
+    pub fn name(self) -> &'static str {
+        match self {
+            DiagnosticKind::UnmappedLetter => "unmapped_letter",
+            DiagnosticKind::OrphanConsonant => "orphan_consonant",
+            DiagnosticKind::UnusualSyllable => "unusual_syllable",
+        }
+    }
+
+    /// 種類の説明を返す。
+    pub fn description(self) -> &'static str {
+        match self {
+            DiagnosticKind::UnmappedLetter => "変換できない英字",
+            DiagnosticKind::OrphanConsonant => "母音が続かない子音",
+            DiagnosticKind::UnusualSyllable => "まれな音節",
+        }
+    }
+}
+
+/// 診断の1件。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// 診断の種類。
+    pub kind: DiagnosticKind,
+    /// 入力のバイト範囲。
+    pub bytes: Range<usize>,
+    /// 入力の文字範囲。
+    pub chars: Range<usize>,
+    /// 該当する入力。
+    pub input: String,
+    /// 該当する出力。
+    pub output: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}..{}: {} ({})", self.chars.start, self.chars.end, self.kind.description(), self.input)?;
+        if self.output != self.input {
+            write!(f, " → {}", self.output)?;
+        }
+        Ok(())
+    }
+}
+
+/// 診断付きの変換結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    /// 変換結果。
+    pub text: String,
+    /// 診断 (入力の位置順)。
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl CheckResult {
+    /// 診断がなければtrue。
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// 入力を変換し、変換結果と診断を返す。
+///
+/// 同じ種類で隣り合う英字はまとめて1件にする (fv → 1件)。
+/// 大文字の扱いが preserve_uppercase のとき、大文字の単語 (KTX) は意図した英字として報告しない。
+pub fn check(converter: &HangulConverter, input: &str, dict: Option<&UserDict>) -> CheckResult {
+    let spans = converter.convert_with_spans(input, dict);
+    let text = spans.iter().map(|span| span.text.as_str()).collect();
+    let preserve_uppercase = converter.options().case == CasePolicy::PreserveUppercase;
+
+    let mut diagnostics: Vec<Diagnostic> = Vec::new();
+    for span in &spans {
+        let Some(kind) = classify(converter, span, preserve_uppercase) else {
+            continue;
+        };
+        let input = &input[span.bytes.clone()];
+        match diagnostics.last_mut() {
+            Some(last) if last.kind == kind && kind != DiagnosticKind::UnusualSyllable && last.bytes.end == span.bytes.start => {
+                last.bytes.end = span.bytes.end;
+                last.chars.end = span.chars.end;
+                last.input.push_str(input);
+                last.output.push_str(&span.text);
+            }
+            _ => diagnostics.push(Diagnostic {
+                kind,
+                bytes: span.bytes.clone(),
+                chars: span.chars.clone(),
+                input: input.to_string(),
+                output: span.text.clone(),
+            }),
+        }
+    }
+    CheckResult { text, diagnostics }
+}
+
+/// 変換結果の1単位を診断する。問題がなければNone。
+fn classify(converter: &HangulConverter, span: &Span, preserve_uppercase: bool) -> Option<DiagnosticKind> {
+    match span.source {
+        SpanSource::Passthrough => {
+            let c = span.text.chars().next().filter(|_| span.text.chars().count() == 1)?;
+            if !c.is_ascii_alphabetic() || (preserve_uppercase && c.is_ascii_uppercase()) {
+                None
+            } else if converter.consonant(&span.text).is_some() {
+                Some(DiagnosticKind::OrphanConsonant)
+            } else {
+                Some(DiagnosticKind::UnmappedLetter)
+            }
+        }
+        SpanSource::Converted => span
+            .text
+            .chars()
+            .any(is_unusual_syllable)
+            .then_some(DiagnosticKind::UnusualSyllable),
+        SpanSource::UserDict | SpanSource::Symbol => None,
+    }
+}
+
+/// まれな音節ならtrue (完成形の音節以外はfalse)。
+pub fn is_unusual_syllable(c: char) -> bool {
+    let Some((cho, jung, _)) = jamo::decompose(c) else {
+        return false;
+    };
+    let rare = match jung.index() {
+        JUNG_YA => RARE_BEFORE_YA.contains(&cho.index()),
+        JUNG_YAE => RARE_BEFORE_YAE.contains(&cho.index()),
+        JUNG_YE => RARE_BEFORE_YE.contains(&cho.index()),
+        _ => false,
+    };
+    rare || !KSX1001_SYLLABLES.lines().any(|line| !line.starts_with('#') && line.contains(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hangul::ConvertOptions;
+
+    fn kinds(input: &str) -> Vec<(DiagnosticKind, Range<usize>, String)> {
+        check(&HangulConverter::new(), input, None)
+            .diagnostics
+            .into_iter()
+            .map(|d| (d.kind, d.chars, d.input))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_input() {
+        let result = check(&HangulConverter::new(), "annyeonghaseyo", None);
+        assert_eq!(result.text, "안녕하세요");
+        assert!(result.is_clean());
+        assert!(check(&HangulConverter::new(), "hangug 2024!", None).is_clean());
+    }
+
+    #[test]
+    fn test_unmapped_letter() {
+        for letter in ["f", "v", "z", "q", "x"] {
+            assert_eq!(kinds(letter), vec![(DiagnosticKind::UnmappedLetter, 0..1, letter.to_string())]);
+        }
+        // 隣り合う英字はまとめる。
+        assert_eq!(kinds("hanfv"), vec![(DiagnosticKind::UnmappedLetter, 3..5, "fv".to_string())]);
+    }
+
+    #[test]
+    fn test_orphan_consonant() {
+        let result = check(&HangulConverter::new(), "hangk", None);
+        assert_eq!(result.text, "항k");
+        assert_eq!(result.diagnostics.len(), 1);
+        let diagnostic = &result.diagnostics[0];
+        assert_eq!(diagnostic.kind, DiagnosticKind::OrphanConsonant);
+        assert_eq!(diagnostic.chars, 4..5);
+        assert_eq!(diagnostic.bytes, 4..5);
+        assert_eq!(diagnostic.to_string(), "4..5: 母音が続かない子音 (k)");
+
+        // 互換字母で出力した子音は報告しない。
+        let converter = HangulConverter::with_options(ConvertOptions { compat_jamo: true, ..Default::default() });
+        assert!(check(&converter, "hangk", None).is_clean());
+    }
+
+    #[test]
+    fn test_unusual_syllable() {
+        let result = check(&HangulConverter::new(), "gyadeul", None);
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].kind, DiagnosticKind::UnusualSyllable);
+        assert_eq!(result.diagnostics[0].chars, 0..3);
+        assert_eq!(result.diagnostics[0].to_string(), "0..3: まれな音節 (gya) → 갸");
+
+        assert!(is_unusual_syllable('갸'));
+        assert!(is_unusual_syllable('뎨'));
+        // KS X 1001 にない音節。
+        assert!(is_unusual_syllable('똠'));
+        assert!(!is_unusual_syllable('걔'));
+        assert!(!is_unusual_syllable('계'));
+        assert!(!is_unusual_syllable('향'));
+        assert!(!is_unusual_syllable('a'));
+    }
+
+    #[test]
+    fn test_preserve_uppercase() {
+        let converter = HangulConverter::with_options(ConvertOptions { case: CasePolicy::PreserveUppercase, ..Default::default() });
+        assert!(check(&converter, "KTX reul", None).is_clean());
+    }
+}
@@ -1,174 +1,883 @@
 //! ハングル変換モジュール。
 
-use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+use std::ops::Range;
+use std::sync::{Arc, Mutex};
+
+use crate::jamo;
+use crate::keyboard::{KeyboardComposer, Layout};
+use crate::normalize::{self, OutputForm};
+use crate::numeral;
+use crate::romaja_table::RomajaTables;
+use crate::trie::KeyTrie;
+use crate::user_dict::UserDict;
+
+/// 変換候補で音節の区切り方を `convert` と変える箇所の上限 (音節群ごと)。
+const MAX_DEVIATIONS: u32 = 2;
+
+/// 変換候補で音節の区切り方を変える範囲 (入力の末尾からの文字数)。
+///
+/// これより前は `convert` と同じ区切り方にし、長い入力でも列挙の量を一定に保つ。
+const CANDIDATE_WINDOW: usize = 32;
+
+/// 両唇音の初声 (ㅁ, ㅂ, ㅃ, ㅍ)。
+const LABIAL_CHOSEONG: [u32; 4] = [6, 7, 8, 17];
+
+/// 入力方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputScheme {
+	/// ローマ字入力 (既定)。
+	#[default]
+	Romaja,
+	/// McCune–Reischauer 式ローマ字入力。
+	McCuneReischauer,
+	/// Yale 式ローマ字入力。
+	Yale,
+	/// 2ボル式 (標準韓国語配列)。
+	Dubeolsik,
+	/// 3ボル式 390。
+	Sebeolsik390,
+	/// 3ボル式 最終。
+	SebeolsikFinal,
+}
+
+impl InputScheme {
+	/// すべての入力方式。
+	pub const ALL: [InputScheme; 6] = [
+		Self::Romaja,
+		Self::McCuneReischauer,
+		Self::Yale,
+		Self::Dubeolsik,
+		Self::Sebeolsik390,
+		Self::SebeolsikFinal,
+	];
+
+	/// 設定ファイルでの名前から入力方式を取得する。
+	pub fn from_name(name: &str) -> Option<Self> {
+		match name {
+			"romaja" => Some(Self::Romaja),
+			"mccune_reischauer" => Some(Self::McCuneReischauer),
+			"yale" => Some(Self::Yale),
+			"dubeolsik" => Some(Self::Dubeolsik),
+			"sebeolsik_390" => Some(Self::Sebeolsik390),
+			"sebeolsik_final" => Some(Self::SebeolsikFinal),
+			_ => None,
+		}
+	}
+
+	/// 設定ファイルでの名前を返す。
+	pub fn name(&self) -> &'static str {
+		match self {
+			Self::Romaja => "romaja",
+			Self::McCuneReischauer => "mccune_reischauer",
+			Self::Yale => "yale",
+			Self::Dubeolsik => "dubeolsik",
+			Self::Sebeolsik390 => "sebeolsik_390",
+			Self::SebeolsikFinal => "sebeolsik_final",
+		}
+	}
+
+	/// ローマ字入力の場合はマッピングテーブルを返す。
+	pub fn romaja_tables(&self) -> Option<RomajaTables> {
+		match self {
+			Self::Romaja => Some(RomajaTables::standard()),
+			Self::McCuneReischauer => Some(RomajaTables::mccune_reischauer()),
+			Self::Yale => Some(RomajaTables::yale()),
+			_ => None,
+		}
+	}
+
+	/// 字母配列入力の場合はキー配列を返す。
+	pub fn layout(&self) -> Option<Layout> {
+		match self {
+			Self::Romaja | Self::McCuneReischauer | Self::Yale => None,
+			Self::Dubeolsik => Some(Layout::Dubeolsik),
+			Self::Sebeolsik390 => Some(Layout::Sebeolsik390),
+			Self::SebeolsikFinal => Some(Layout::SebeolsikFinal),
+		}
+	}
+}
+
+/// 音節区切りの方式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SyllableBreak {
+	/// スペース1つで音節区切り、2つで実際のスペース (既定)。
+	#[default]
+	Space,
+	/// 指定した記号1つで音節区切り、2つでその記号そのもの。スペースはそのまま出力する。
+	Char(char),
+}
+
+impl SyllableBreak {
+	/// 設定ファイルでの名前 (`"space"` または記号1文字) から区切り方式を取得する。
+	pub fn from_name(name: &str) -> Option<Self> {
+		if name == "space" {
+			return Some(Self::Space);
+		}
+		let mut chars = name.chars();
+		match (chars.next(), chars.next()) {
+			(Some(c), None) if c.is_ascii_punctuation() => Some(Self::Char(c)),
+			_ => None,
+		}
+	}
+
+	/// 設定ファイルでの名前を返す。
+	pub fn name(&self) -> String {
+		match self {
+			Self::Space => "space".to_string(),
+			Self::Char(c) => c.to_string(),
+		}
+	}
+
+	/// 音節区切りとして扱う文字を返す。
+	pub fn break_char(&self) -> char {
+		match self {
+			Self::Space => ' ',
+			Self::Char(c) => *c,
+		}
+	}
+}
+
+/// ローマ字入力での大文字の扱い。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CasePolicy {
+	/// 大文字を小文字として扱う (既定)。
+	#[default]
+	Ignore,
+	/// 大文字 (Shift) でキーの先頭の字母を濃音・ㅣ系の二重母音にする (G → ㄲ, Eo → ㅕ)。
+	///
+	/// 子音は先頭を重ねたキー (g → gg)、母音は先頭に y を付けたキー (a → ya) の字母になる。
+	/// 対応するキーがない大文字は小文字として扱う。
+	Shift,
+	/// すべて大文字の英字の並び (2文字以上) を英字のまま出力する (KTX, SNS)。
+	PreserveUppercase,
+}
+
+impl CasePolicy {
+	/// すべての大文字の扱い。
+	pub const ALL: [CasePolicy; 3] = [CasePolicy::Ignore, CasePolicy::Shift, CasePolicy::PreserveUppercase];
+
+	/// 設定ファイルでの名前から大文字の扱いを取得する。
+	pub fn from_name(name: &str) -> Option<Self> {
+		Self::ALL.into_iter().find(|policy| policy.name() == name)
+	}
+
+	/// 設定ファイルでの名前を返す。
+	pub fn name(self) -> &'static str {
+		match self {
+			CasePolicy::Ignore => "ignore",
+			CasePolicy::Shift => "shift",
+			CasePolicy::PreserveUppercase => "preserve_uppercase",
+		}
+	}
+}
+
+/// 変換オプション。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConvertOptions {
+	/// 母音が続かない子音を互換字母 (ㅋ, ㅎ など) で出力するか。
+	///
+	/// falseの場合はローマ字のまま出力する。終声にしかない綴り (ng, lg, bs など) は
+	/// 1字母 (ㅇ, ㄺ, ㅄ) にまとめる。母音は常に初声 ㅇ を補って音節にする。
+	pub compat_jamo: bool,
+	/// 音節区切りの方式。
+	///
+	/// 区切り記号がテーブルのキーや区切り文字と重なる場合は区切りが優先される。
+	pub syllable_break: SyllableBreak,
+	/// 古語の字母 (ㆍ, ㅿ, ㆁ, ㆆ など) の綴りを受け付けるか。
+	///
+	/// 古語の字母を含む音節は完成形にならないため、結合型字母の列
+	/// (U+1100〜U+11FF, U+A960〜, U+D7B0〜) で出力する。
+	pub archaic: bool,
+	/// 音節の出力形式 (完成形・結合型字母・互換字母)。
+	pub form: OutputForm,
+	/// 大文字の扱い。
+	pub case: CasePolicy,
+	/// 記号の綴り (<< → 《, \! → ！ など) を記号に変換するか。
+	///
+	/// 綴りはテーブルの `symbols` で定義する。ローマ字の綴り・音節区切りが優先される。
+	pub symbols: bool,
+}
+
+/// 変換候補。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+	/// 変換結果。
+	pub text: String,
+	/// スコア (0 が最良、負の値は逸脱の数)。
+	pub score: i32,
+}
+
+/// 変換結果の単位の由来。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpanSource {
+	/// ローマ字から変換した音節・字母。
+	Converted,
+	/// 変換せずそのまま出力した文字 (母音が続かない子音・記号・スペースなど)。
+	Passthrough,
+	/// ユーザー辞書の値。
+	UserDict,
+	/// 記号の綴りから変換した記号。
+	Symbol,
+}
+
+impl SpanSource {
+	/// 由来の名前を返す。
+	pub fn name(self) -> &'static str {
+		match self {
+			SpanSource::Converted => "converted",
+			SpanSource::Passthrough => "passthrough",
+			SpanSource::UserDict => "user_dict",
+			SpanSource::Symbol => "symbol",
+		}
+	}
+}
+
+/// 変換結果の1単位と、それを生成した入力の範囲。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Span {
+	/// 出力文字列 (1音節、または変換しなかった文字)。
+	pub text: String,
+	/// 入力のバイト範囲。
+	pub bytes: Range<usize>,
+	/// 入力の文字範囲。
+	pub chars: Range<usize>,
+	/// 出力の由来。
+	pub source: SpanSource,
+}
+
+/// 区切りで分けた入力の断片 (小文字化した入力での文字範囲)。
+enum Segment {
+	/// 変換する音節群。
+	Group(Range<usize>),
+	/// そのまま出力する文字 (スペース・区切り記号) とその個数。
+	Literal(Range<usize>, char, usize),
+}
+
+/// 入力を区切りで断片に分けるイテレータ。
+struct Segments<'a> {
+	/// 小文字化した入力。
+	chars: &'a [char],
+	/// 音節区切りの文字。
+	break_char: char,
+	/// 次に調べる位置。
+	pos: usize,
+	/// 現在の音節群の開始位置。
+	group_start: usize,
+	/// 音節群の次に返す断片。
+	pending: Option<Segment>,
+}
+
+impl Iterator for Segments<'_> {
+	type Item = Segment;
+
+	fn next(&mut self) -> Option<Segment> {
+		loop {
+			if let Some(segment) = self.pending.take() {
+				return Some(segment);
+			}
+
+			if self.pos >= self.chars.len() {
+				// 最後の音節群。
+				let group = self.group_start..self.pos;
+				self.group_start = self.pos;
+				return (!group.is_empty()).then_some(Segment::Group(group));
+			}
+
+			let c = self.chars[self.pos];
+			if c != self.break_char && c != ' ' {
+				self.pos += 1;
+				continue;
+			}
+
+			let group = self.group_start..self.pos;
+			let literal = if c != self.break_char {
+				// 区切りが記号ならスペースはそのまま。
+				self.pos += 1;
+				Some(Segment::Literal(self.pos - 1..self.pos, ' ', 1))
+			} else {
+				// 区切り2つごとに区切り文字そのものを出力。
+				let start = self.pos;
+				let count = self.chars[start..].iter().take_while(|&&ch| ch == self.break_char).count();
+				self.pos += count;
+				(count >= 2).then_some(Segment::Literal(start..self.pos, self.break_char, count / 2))
+			};
+			self.group_start = self.pos;
+
+			// 現在の音節群を区切る。
+			if !group.is_empty() {
+				self.pending = literal;
+				return Some(Segment::Group(group));
+			}
+			if literal.is_some() {
+				return literal;
+			}
+		}
+	}
+}
+
+/// 1文字を小文字にする (小文字が複数の文字になる場合はそのまま)。
+fn lowercase_char(c: char) -> char {
+	let mut lower = c.to_lowercase();
+	match (lower.next(), lower.next()) {
+		(Some(l), None) => l,
+		_ => c,
+	}
+}
+
+/// `end` で終わる英字の並び (末尾が英字でなければその1文字) の開始位置を返す。
+fn run_start(chars: &[char], end: usize) -> usize {
+	let letters = chars[..end].iter().rev().take_while(|c| c.is_alphabetic()).count();
+	end - letters.max(1).min(end)
+}
+
+/// 入力方式・変換オプションごとに共有する標準テーブルの変換器。
+static SHARED: Mutex<Vec<(InputScheme, ConvertOptions, Arc<HangulConverter>)>> = Mutex::new(Vec::new());
 
 /// ハングル変換器。
+///
+/// 作成後は変更されないため、[`HangulConverter::shared`] や `Arc` で複数の合成器から共有できる。
+/// 変換中の検索はトライで行い、入力の文字ごとの割り当ては発生しない。
 pub struct HangulConverter {
 	/// 初声(子音)マッピング。
-	choseong: HashMap<&'static str, u32>,
+	choseong: KeyTrie,
 	/// 中声(母音)マッピング。
-	jungseong: HashMap<&'static str, u32>,
+	jungseong: KeyTrie,
 	/// 終声(パッチム)マッピング。
-	jongseong: HashMap<&'static str, u32>,
+	jongseong: KeyTrie,
+	/// 両唇音の直後で優先する中声マッピング。
+	labial_jungseong: KeyTrie,
+	/// 記号の綴り → 記号のインデックス (記号入力が無効なら空)。
+	symbols: KeyTrie,
+	/// 記号 (`symbols` の値のインデックス順)。
+	symbol_values: Vec<String>,
+	/// 各テーブルのキーの最大文字数 (初声, 中声, 終声)。
+	max_len: (usize, usize, usize),
+	/// 初声・中声・終声 (記号入力が有効なら記号) のキーに含まれる文字 (ソート済み)。
+	key_chars: Vec<char>,
+	/// 音節区切りとして読み捨てる文字。
+	separators: Vec<char>,
+	/// 変換オプション。
+	options: ConvertOptions,
 }
 
 impl HangulConverter {
 	/// 新しい変換器を作成する。
 	pub fn new() -> Self {
+		Self::with_options(ConvertOptions::default())
+	}
+
+	/// 入力方式のテーブルで変換器を作成する。
+	///
+	/// 字母配列入力の場合は標準テーブルを使用する。
+	pub fn with_scheme(scheme: InputScheme, options: ConvertOptions) -> Self {
+		Self::with_tables(scheme.romaja_tables().unwrap_or_default(), options)
+	}
+
+	/// 入力方式の標準テーブルの変換器を、同じ入力方式・オプションの呼び出し元と共有する。
+	///
+	/// 初回の呼び出しで作成し、以降は同じ変換器を返す。
+	pub fn shared(scheme: InputScheme, options: ConvertOptions) -> Arc<Self> {
+		let mut shared = SHARED.lock().unwrap_or_else(|e| e.into_inner());
+		if let Some((_, _, converter)) = shared.iter().find(|(s, o, _)| *s == scheme && *o == options) {
+			return Arc::clone(converter);
+		}
+		let converter = Arc::new(Self::with_scheme(scheme, options));
+		shared.push((scheme, options, Arc::clone(&converter)));
+		converter
+	}
+
+	/// オプションを指定して変換器を作成する。
+	pub fn with_options(options: ConvertOptions) -> Self {
+		Self::with_tables(RomajaTables::standard(), options)
+	}
+
+	/// マッピングテーブルとオプションを指定して変換器を作成する。
+	pub fn with_tables(tables: RomajaTables, options: ConvertOptions) -> Self {
+		let tables = if options.archaic { tables.with_archaic() } else { tables };
+		let tables = match options.case {
+			CasePolicy::Shift => tables.with_shift_variants(),
+			CasePolicy::Ignore | CasePolicy::PreserveUppercase => tables,
+		};
+		let symbols: Vec<(String, String)> = match options.symbols {
+			true => tables.symbols.into_iter().collect(),
+			false => Vec::new(),
+		};
+		let mut key_chars: Vec<char> = [&tables.choseong, &tables.jungseong, &tables.jongseong]
+			.iter()
+			.flat_map(|map| map.keys().flat_map(|key| key.chars()))
+			.chain(symbols.iter().flat_map(|(key, _)| key.chars()))
+			.collect();
+		key_chars.sort_unstable();
+		key_chars.dedup();
+		let symbol_keys = symbols.iter().enumerate().map(|(i, (key, _))| (key.clone(), i as u32)).collect();
+
+		let choseong = KeyTrie::new(&tables.choseong);
+		let jungseong = KeyTrie::new(&tables.jungseong);
+		let jongseong = KeyTrie::new(&tables.jongseong);
+		let labial_jungseong = KeyTrie::new(&tables.labial_jungseong);
 		Self {
-			choseong: Self::build_choseong(),
-			jungseong: Self::build_jungseong(),
-			jongseong: Self::build_jongseong(),
+			max_len: (
+				choseong.max_len(),
+				jungseong.max_len().max(labial_jungseong.max_len()),
+				jongseong.max_len(),
+			),
+			choseong,
+			jungseong,
+			jongseong,
+			labial_jungseong,
+			symbols: KeyTrie::new(&symbol_keys),
+			symbol_values: symbols.into_iter().map(|(_, value)| value).collect(),
+			key_chars,
+			separators: tables.separators,
+			options,
 		}
 	}
 
-	/// 初声マッピングを構築する。
-	fn build_choseong() -> HashMap<&'static str, u32> {
-		[
-			("g", 0), ("gg", 1),("kk", 1), ("n", 2), ("d", 3), ("dd", 4), ("tt", 4),
-			("r", 5), ("l", 5), ("m", 6), ("b", 7), ("bb", 8), ("pp", 8),
-			("s", 9), ("ss", 10), ("j", 12), ("jj", 13),
-			("ch", 14), ("k", 15), ("t", 16), ("p", 17), ("h", 18),
-		]
-			.into_iter()
-			.collect()
+	/// 英字またはテーブルのキー・区切りに含まれる文字か判定する (大文字は小文字として扱う)。
+	pub fn is_key_char(&self, c: char) -> bool {
+		let c = c.to_ascii_lowercase();
+		c.is_ascii_alphabetic() || self.separators.contains(&c) || self.key_chars.binary_search(&c).is_ok()
 	}
 
-	/// 中声マッピングを構築する。
-	fn build_jungseong() -> HashMap<&'static str, u32> {
-		[
-			("a", 0), ("ae", 1), ("ya", 2), ("yae", 3), ("eo", 4),
-			("e", 5), ("yeo", 6), ("ye", 7), ("o", 8), ("wa", 9),
-			("wae", 10), ("oe", 11), ("yo", 12), ("u", 13), ("wo", 14),
-			("we", 15), ("wi", 16), ("yu", 17), ("eu", 18), ("ui", 19),
-			("i", 20),
-		]
-			.into_iter()
-			.collect()
+	/// 変換オプションを返す。
+	pub fn options(&self) -> &ConvertOptions {
+		&self.options
 	}
 
-	/// 終声マッピングを構築する。
-	fn build_jongseong() -> HashMap<&'static str, u32> {
-		[
-			("g", 1), ("gg", 2), ("gs", 3), ("n", 4),
-			("nj", 5), ("nh", 6), ("d", 7), ("l", 8), ("lg", 9),
-			("lm", 10), ("lb", 11), ("ls", 12), ("lt", 13), ("lp", 14),
-			("lh", 15), ("m", 16), ("b", 17), ("bs", 18), ("s", 19),
-			("ss", 20), ("ng", 21), ("j", 22), ("ch", 23), ("k", 24),
-			("t", 25), ("p", 26), ("h", 27),
-		]
-			.into_iter()
-			.collect()
+	/// 入力全体が初声1つの綴りなら、その子音を互換字母で返す (m → ㅁ, kk → ㄲ)。
+	///
+	/// 記号パレット (子音 + 漢字キー) の子音の判定に使う。
+	pub fn consonant(&self, input: &str) -> Option<char> {
+		let chars: Vec<char> = input.chars().map(lowercase_char).collect();
+		match self.find_choseong(&chars, 0) {
+			Some((cho, len)) if len == chars.len() => jamo::choseong_compat(cho),
+			_ => None,
+		}
+	}
+
+	/// 入力を大文字の扱いに従って変換用の文字の列にする (1文字 → 1文字)。
+	fn fold_case(&self, input: &str) -> Vec<char> {
+		let mut chars: Vec<char> = input.chars().collect();
+		let mut end = chars.len();
+		while end > 0 {
+			let start = run_start(&chars, end);
+			self.fold_run(&mut chars[start..end]);
+			end = start;
+		}
+		chars
+	}
+
+	/// 英字の並び (または英字以外の1文字) を大文字の扱いに従って変換用の文字にする。
+	///
+	/// 大文字のままにする文字はテーブルのキーにない限り変換されず、英字のまま出力される。
+	fn fold_run(&self, run: &mut [char]) {
+		let keep_upper = match self.options.case {
+			CasePolicy::PreserveUppercase => run.len() >= 2 && run.iter().all(|c| c.is_uppercase()),
+			CasePolicy::Ignore | CasePolicy::Shift => false,
+		};
+		for c in run.iter_mut() {
+			let shift_key = self.options.case == CasePolicy::Shift && self.key_chars.binary_search(c).is_ok();
+			if !keep_upper && !shift_key {
+				*c = lowercase_char(*c);
+			}
+		}
+	}
+
+	/// 確定判定に必要な先読み文字数。
+	///
+	/// 1単位の変換は開始位置から最大で初声・中声・終声・次の初声・次の中声の
+	/// 各最長キーの合計文字数しか参照しないため、これ以上の入力があれば
+	/// 先頭の単位は後続の入力で変化しない。標準テーブルでは12文字。
+	/// 記号の綴りの方が長い場合はその文字数。
+	fn stable_window(&self) -> usize {
+		let (cho, jung, jong) = self.max_len;
+		((cho + jung) * 2 + jong).max(self.symbols.max_len())
 	}
 
 	/// ローマ字をハングルに変換する。
+	///
+	/// 区切り (既定ではスペース) 1つで音節を区切り、2つで区切り文字そのものを出力する。
+	/// 区切りが記号の場合、スペースはそのまま出力する。
 	pub fn convert(&self, input: &str) -> String {
-		let input = input.to_lowercase();
-		let mut result = String::new();
-		let mut chars = input.chars().peekable();
-		let mut current_syllable = String::new();
-
-		while let Some(c) = chars.next() {
-			if c == ' ' {
-				// スペースの連続をカウント。
-				let mut space_count = 1;
-				while chars.peek() == Some(&' ') {
-					chars.next();
-					space_count += 1;
+		let mut result = String::with_capacity(input.len());
+		// String への書き込みは失敗しない。
+		let _ = self.convert_into(input, &mut result);
+		result
+	}
+
+	/// ローマ字をハングルに変換して `out` に書き込む。
+	///
+	/// 結果は `convert` と同じ。入力の小文字化と1単位分の作業領域以外の割り当ては行わない。
+	pub fn convert_into(&self, input: &str, out: &mut impl fmt::Write) -> fmt::Result {
+		let chars = self.fold_case(input);
+		let mut unit = String::new();
+		for segment in self.segments(&chars) {
+			match segment {
+				Segment::Group(range) => {
+					let group = &chars[range];
+					let mut pos = 0;
+					while pos < group.len() {
+						unit.clear();
+						pos = self.convert_unit(group, pos, &mut unit);
+						out.write_str(&unit)?;
+					}
+				}
+				Segment::Literal(_, c, count) => {
+					for _ in 0..count {
+						out.write_char(c)?;
+					}
 				}
+			}
+		}
+		Ok(())
+	}
+
+	/// ローマ字をハングルに変換し、音節の区切り方が異なる候補をスコア順に最大 `limit` 件返す。
+	///
+	/// 先頭は常に `convert` の結果 (スコア 0)。子音を終声にするか次の音節の初声にするかの
+	/// 選択を `convert` と変えた箇所ごとにスコアが1下がる (gangi → 간기, 강이)。
+	/// 同じスコアでは、終声の後に初声 ㅇ の音節が続く箇所が少ない候補を優先する。
+	/// 区切り方を変えるのは入力の末尾 [`CANDIDATE_WINDOW`] 文字の範囲のみ。
+	pub fn convert_candidates(&self, input: &str, limit: usize) -> Vec<Candidate> {
+		self.candidates_chars(&self.fold_case(input), "", limit)
+	}
 
-				// 現在の音節を変換。
-				if !current_syllable.is_empty() {
-					result.push_str(&self.convert_syllable(&current_syllable));
-					current_syllable.clear();
+	/// 変換用の文字の列の変換候補を、各候補の先頭に `prefix` を付けて返す。
+	///
+	/// 末尾 [`CANDIDATE_WINDOW`] 文字より前は `convert` と同じく変換して全候補で共有し、
+	/// 候補ごとの文字列は最後に1回だけ組み立てる。
+	fn candidates_chars(&self, chars: &[char], prefix: &str, limit: usize) -> Vec<Candidate> {
+		let window_start = chars.len().saturating_sub(CANDIDATE_WINDOW);
+		// 全候補に共通の変換結果。
+		let mut prefix = prefix.to_string();
+		// 共通部分に続く (変換結果, 選択を変えた箇所の数, 終声の後の初声 ㅇ の数)。
+		let mut results: Vec<(String, u32, u32)> = vec![(String::new(), 0, 0)];
+		for segment in self.segments(chars) {
+			match segment {
+				Segment::Group(range) if range.end <= window_start => {
+					self.convert_chars(&chars[range], &mut prefix);
+				}
+				Segment::Group(range) => {
+					let group = &chars[range.clone()];
+					// 範囲の開始位置より前の単位は `convert` と同じく変換する。
+					let mut start = 0;
+					while range.start + start < window_start {
+						start = self.convert_unit(group, start, &mut prefix);
+					}
+					let group = self.group_candidates(group, start);
+					results = results
+						.iter()
+						.flat_map(|r| {
+							group.iter().map(move |g| (format!("{}{}", r.0, g.0), r.1 + g.1, r.2 + g.2))
+						})
+						.collect();
+					results.sort_by_key(|r| (r.1, r.2));
+					let mut seen = HashSet::new();
+					results.retain(|r| seen.insert(r.0.clone()));
+					results.truncate(limit);
+				}
+				Segment::Literal(range, c, count) => {
+					let literal = c.to_string().repeat(count);
+					if range.end <= window_start {
+						prefix.push_str(&literal);
+					} else {
+						for r in &mut results {
+							r.0.push_str(&literal);
+						}
+					}
 				}
+			}
+		}
+
+		results
+			.into_iter()
+			.take(limit)
+			.map(|(text, deviations, _)| Candidate {
+				text: format!("{}{}", prefix, text),
+				score: -(deviations as i32),
+			})
+			.collect()
+	}
 
-				// スペース2つごとに実際のスペースを出力。
-				for _ in 0..(space_count / 2) {
-					result.push(' ');
+	/// ローマ字をハングルに変換し、出力の単位ごとに入力の範囲と由来を返す。
+	///
+	/// 単位は1音節、または変換しなかった1文字 (区切りが記号の場合のスペースなど)。
+	/// 出力のない入力 (音節区切り・アポストロフィなど) はどの単位にも含まれない。
+	/// ユーザー辞書に入力全体 (小文字化後) が完全一致した場合は、辞書の値 (テンプレートは展開したもの) を入力全体の1単位として返す。
+	/// 辞書がない (`dict` がNone) か辞書に一致しない場合、各単位の `text` を連結すると `convert` の結果と一致する。
+	pub fn convert_with_spans(&self, input: &str, dict: Option<&UserDict>) -> Vec<Span> {
+		// 変換用の文字の列は入力と1文字ずつ対応する。
+		let chars = self.fold_case(input);
+		let offsets: Vec<usize> = input.char_indices().map(|(i, _)| i).chain([input.len()]).collect();
+		let span = |range: Range<usize>, text: String, source: SpanSource| Span {
+			text,
+			bytes: offsets[range.start]..offsets[range.end],
+			chars: range,
+			source,
+		};
+
+		if let Some(value) = dict.and_then(|d| d.resolve(&input.to_lowercase(), self)) {
+			return vec![span(0..chars.len(), value, SpanSource::UserDict)];
+		}
+
+		let mut spans = Vec::new();
+		for segment in self.segments(&chars) {
+			match segment {
+				Segment::Group(range) => {
+					let chars = &chars[range.clone()];
+					let mut pos = 0;
+					while pos < chars.len() {
+						let mut text = String::new();
+						let end = self.convert_unit(chars, pos, &mut text);
+						if !text.is_empty() {
+							// 入力をそのまま出力した単位は未変換。
+							let symbol = self.find_symbol(chars, pos).filter(|&(s, len)| pos + len == end && s == text);
+							let source = if text.chars().eq(chars[pos..end].iter().copied()) {
+								SpanSource::Passthrough
+							} else if symbol.is_some() {
+								SpanSource::Symbol
+							} else {
+								SpanSource::Converted
+							};
+							spans.push(span(range.start + pos..range.start + end, text, source));
+						}
+						pos = end;
+					}
+				}
+				Segment::Literal(range, c, count) => {
+					let text = c.to_string().repeat(count);
+					spans.push(span(range, text, SpanSource::Passthrough));
 				}
-			} else {
-				current_syllable.push(c);
 			}
 		}
+		spans
+	}
 
-		// 最後の音節を変換。
-		if !current_syllable.is_empty() {
-			result.push_str(&self.convert_syllable(&current_syllable));
+	/// 小文字化した入力を区切りで音節群とそのまま出力する文字に分ける。
+	fn segments<'a>(&self, chars: &'a [char]) -> Segments<'a> {
+		Segments {
+			chars,
+			break_char: self.options.syllable_break.break_char(),
+			pos: 0,
+			group_start: 0,
+			pending: None,
 		}
+	}
 
-		result
+	/// 音節群の `start` 以降の変換候補を (変換結果, 選択を変えた箇所の数, 終声の後の初声 ㅇ の数) で返す。
+	///
+	/// 選択を変える箇所は最大 [`MAX_DEVIATIONS`] まで。
+	fn group_candidates(&self, chars: &[char], start: usize) -> Vec<(String, u32, u32)> {
+		let mut out = Vec::new();
+		self.collect_candidates(chars, start, &mut String::new(), (0, 0), false, &mut out);
+		out.sort_by_key(|c| (c.1, c.2));
+		out
 	}
 
-	/// 単一の音節群を変換する (区切りなし)。
-	fn convert_syllable(&self, input: &str) -> String {
-		let chars: Vec<char> = input.chars().collect();
-		let mut result = String::new();
+	/// `pos` 以降の変換候補を深さ優先で `out` に追加する。
+	///
+	/// 各音節で `find_jongseong_with_lookahead` と同じ選択に加え、次が音節の始まりになる
+	/// 別の長さの終声 (なしを含む) を試す。
+	fn collect_candidates(
+		&self,
+		chars: &[char],
+		pos: usize,
+		text: &mut String,
+		cost: (u32, u32),
+		after_jong: bool,
+		out: &mut Vec<(String, u32, u32)>,
+	) {
+		if pos >= chars.len() {
+			out.push((text.clone(), cost.0, cost.1));
+			return;
+		}
+
+		let text_len = text.len();
+		let (cho_idx, jung_pos) = match self.find_choseong(chars, pos) {
+			Some((idx, len)) => (Some(idx), pos + len),
+			None => (None, pos),
+		};
+		let jung = match cho_idx {
+			Some(idx) => self.find_jungseong_after(idx, chars, jung_pos),
+			None => self.find_jungseong(chars, jung_pos),
+		};
+		let Some((jung_idx, jung_len)) = jung else {
+			// 音節にならない単位は `convert` と同じく変換する。
+			let next = self.convert_unit(chars, pos, text);
+			self.collect_candidates(chars, next, text, cost, false, out);
+			text.truncate(text_len);
+			return;
+		};
+
+		let jong_pos = jung_pos + jung_len;
+		let mut greedy_end = jong_pos;
+		self.find_jongseong_with_lookahead(chars, &mut greedy_end);
+		let null_onsets = cost.1 + u32::from(cho_idx.is_none() && after_jong);
+
+		for len in (0..=self.max_len.2).rev() {
+			let end = jong_pos + len;
+			if end > chars.len() {
+				continue;
+			}
+			let jong_idx = if len == 0 {
+				0
+			} else {
+				match self.jongseong.get(&chars[jong_pos..end]) {
+					Some(idx) => idx,
+					None => continue,
+				}
+			};
+			let deviations = cost.0 + u32::from(end != greedy_end);
+			if end != greedy_end && (deviations > MAX_DEVIATIONS || !self.starts_syllable(chars, end)) {
+				continue;
+			}
+
+			// 11 = ㅇ (無音の初声)。
+			normalize::push_syllable(cho_idx.unwrap_or(11), jung_idx, jong_idx, self.options.form, text);
+			self.collect_candidates(chars, end, text, (deviations, null_onsets), jong_idx != 0, out);
+			text.truncate(text_len);
+		}
+	}
+
+	/// `pos` から音節が始まるか (入力の終わりを含む) 判定する。
+	fn starts_syllable(&self, chars: &[char], pos: usize) -> bool {
+		if pos >= chars.len() {
+			return true;
+		}
+		match self.find_choseong(chars, pos) {
+			Some((idx, len)) if self.find_jungseong_after(idx, chars, pos + len).is_some() => true,
+			_ => self.find_jungseong(chars, pos).is_some(),
+		}
+	}
+
+	/// 文字列の音節群を変換して `out` に追加する (区切りなし)。
+	fn convert_chars(&self, chars: &[char], out: &mut String) {
 		let mut pos = 0;
 
 		while pos < chars.len() {
-			// 初声を探す。
-			match self.find_choseong(&chars, pos) {
-				Some((cho_idx, cho_len)) => {
-					pos += cho_len;
+			pos = self.convert_unit(chars, pos, out);
+		}
+	}
 
-					// 中声を探す。
-					match self.find_jungseong(&chars, pos) {
-						Some((jung_idx, jung_len)) => {
-							pos += jung_len;
+	/// `pos` から1単位 (1音節または未変換の文字) を変換して `out` に追加する。
+	///
+	/// 次の単位の開始位置を返す。
+	fn convert_unit(&self, chars: &[char], mut pos: usize, out: &mut String) -> usize {
+		// 初声を探す。
+		match self.find_choseong(chars, pos) {
+			Some((cho_idx, cho_len)) => {
+				pos += cho_len;
+
+				// 中声を探す。
+				match self.find_jungseong_after(cho_idx, chars, pos) {
+					Some((jung_idx, jung_len)) => {
+						pos += jung_len;
 
-							// 終声を探す(次の音節との境界判定)。
-							let jong_idx = self.find_jongseong_with_lookahead(&chars, &mut pos);
+						// 終声を探す(次の音節との境界判定)。
+						let jong_idx = self.find_jongseong_with_lookahead(chars, &mut pos);
 
-							// ハングル文字を合成。
-							result.push(self.compose(cho_idx, jung_idx, jong_idx));
-						}
-						None => {
-							// 中声がない場合、子音をそのまま出力。
-							for i in 0..cho_len {
-								result.push(chars[pos - cho_len + i]);
-							}
+						// ハングル文字を合成 (出力形式に従う)。
+						normalize::push_syllable(cho_idx, jung_idx, jong_idx, self.options.form, out);
+					}
+					None if self.options.compat_jamo => {
+						pos = self.convert_orphan(chars, pos - cho_len, cho_idx, cho_len, out);
+					}
+					None => {
+						// 中声がない場合、子音をそのまま出力。
+						for i in 0..cho_len {
+							out.push(chars[pos - cho_len + i]);
 						}
 					}
 				}
-				None => {
-					// 母音から始まる場合(初声 = ㅇ)。
-					if let Some((jung_idx, jung_len)) = self.find_jungseong(&chars, pos) {
-						pos += jung_len;
-						let jong_idx = self.find_jongseong_with_lookahead(&chars, &mut pos);
-						// 11 = ㅇ (無音の初声)。
-						result.push(self.compose(11, jung_idx, jong_idx));
-					} else {
-						// マッチしない文字はそのまま。
-						result.push(chars[pos]);
-						pos += 1;
+			}
+			None => {
+				// 母音から始まる場合(初声 = ㅇ)。
+				if let Some((jung_idx, jung_len)) = self.find_jungseong(chars, pos) {
+					pos += jung_len;
+					let jong_idx = self.find_jongseong_with_lookahead(chars, &mut pos);
+					// 11 = ㅇ (無音の初声)。
+					normalize::push_syllable(11, jung_idx, jong_idx, self.options.form, out);
+				} else if let Some((symbol, len)) = self.find_symbol(chars, pos) {
+					// 記号の綴り (ローマ字の綴りにならない場合のみ)。
+					out.push_str(symbol);
+					pos += len;
+				} else {
+					// マッチしない文字はそのまま (区切り文字は読み捨てる)。
+					if !self.separators.contains(&chars[pos]) {
+						out.push(chars[pos]);
 					}
+					pos += 1;
 				}
 			}
 		}
 
-		result
+		pos
+	}
+
+	/// 母音が続かない子音を互換字母で出力する。
+	///
+	/// 終声にしかない綴り (ng, lg など) で後ろに母音が続かなければ1字母にまとめる。
+	/// 次の単位の開始位置を返す。
+	fn convert_orphan(
+		&self,
+		chars: &[char],
+		pos: usize,
+		cho_idx: u32,
+		cho_len: usize,
+		out: &mut String,
+	) -> usize {
+		let cluster_end = pos + 2;
+		if cluster_end <= chars.len() && self.find_jungseong(chars, cluster_end).is_none() {
+			if let Some(jong) = self.jongseong.get(&chars[pos..cluster_end]).and_then(jamo::jongseong_compat) {
+				out.push(jong);
+				return cluster_end;
+			}
+		}
+
+		// 互換字母のない古語の字母は結合型字母で出力する。
+		let compat = jamo::choseong_compat(cho_idx).or_else(|| jamo::choseong_char(cho_idx));
+		out.push(compat.unwrap_or('?'));
+		pos + cho_len
+	}
+
+	/// 記号の綴りを検索する(最長一致)。記号入力が無効なら常にNone。
+	fn find_symbol(&self, chars: &[char], pos: usize) -> Option<(&str, usize)> {
+		let (index, len) = self.symbols.longest_match(chars, pos)?;
+		Some((&self.symbol_values[index as usize], len))
 	}
 
 	/// 初声を検索する(最長一致)。
 	fn find_choseong(&self, chars: &[char], pos: usize) -> Option<(u32, usize)> {
-		self.find_longest_match(chars, pos, &self.choseong)
+		self.choseong.longest_match(chars, pos)
 	}
 
 	/// 中声を検索する(最長一致)。
 	fn find_jungseong(&self, chars: &[char], pos: usize) -> Option<(u32, usize)> {
-		self.find_longest_match(chars, pos, &self.jungseong)
+		self.jungseong.longest_match(chars, pos)
+	}
+
+	/// 初声に続く中声を検索する(最長一致、両唇音の後は専用マッピングを優先)。
+	fn find_jungseong_after(&self, cho: u32, chars: &[char], pos: usize) -> Option<(u32, usize)> {
+		let jung = self.find_jungseong(chars, pos);
+		if !LABIAL_CHOSEONG.contains(&cho) {
+			return jung;
+		}
+		match self.labial_jungseong.longest_match(chars, pos) {
+			Some(labial) if jung.is_none_or(|(_, len)| labial.1 >= len) => Some(labial),
+			_ => jung,
+		}
 	}
 
 	/// 終声を検索する(次の音節を考慮)。
 	fn find_jongseong_with_lookahead(&self, chars: &[char], pos: &mut usize) -> u32 {
-		for len in (1..=2).rev() {
+		for len in (1..=self.max_len.2).rev() {
 			if *pos + len > chars.len() {
 				continue;
 			}
 
-			let substr: String = chars[*pos..*pos + len].iter().collect();
-			if let Some(&jong_idx) = self.jongseong.get(substr.as_str()) {
+			if let Some(jong_idx) = self.jongseong.get(&chars[*pos..*pos + len]) {
 				let next_pos = *pos + len;
 
 				if next_pos >= chars.len() {
@@ -178,8 +887,8 @@ impl HangulConverter {
 				}
 
 				// 次に子音があるかチェック。
-				if let Some((_, cho_len)) = self.find_longest_match(chars, next_pos, &self.choseong) {
-					if self.find_jungseong(chars, next_pos + cho_len).is_some() {
+				if let Some((cho_idx, cho_len)) = self.find_choseong(chars, next_pos) {
+					if self.find_jungseong_after(cho_idx, chars, next_pos + cho_len).is_some() {
 						// 子音+母音パターン → 終声採用 (子音が次の初声になる)。
 						*pos = next_pos;
 						return jong_idx;
@@ -194,6 +903,13 @@ impl HangulConverter {
 					continue;
 				}
 
+				// 終声の子音から始まる長い初声 (k' など) の後に母音が続く → 次の音節の初声。
+				if let Some((cho_idx, cho_len)) = self.find_choseong(chars, *pos) {
+					if cho_len > len && self.find_jungseong_after(cho_idx, chars, *pos + cho_len).is_some() {
+						continue;
+					}
+				}
+
 				// その他 (マッチしない文字) → 終声採用。
 				*pos = next_pos;
 				return jong_idx;
@@ -203,37 +919,367 @@ impl HangulConverter {
 		// 終声なし。
 		0
 	}
+}
 
-	/// マップから最長一致で検索する。
-	fn find_longest_match(
-		&self,
-		chars: &[char],
-		pos: usize,
-		map: &HashMap<&'static str, u32>,
-	) -> Option<(u32, usize)> {
-		// 長い方から試す(最長一致)。
-		for len in (1..=3).rev() {
-			if pos + len > chars.len() {
-				continue;
+impl Default for HangulConverter {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+/// [`Composer`] の入力操作の結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComposerUpdate {
+	/// この操作で確定を取り消し、未確定に戻した文字列 (確定済みの末尾から除かれる)。
+	pub retracted: String,
+	/// この操作で新たに確定した文字列。
+	pub committed: String,
+	/// 未確定の入力中文字列 (プリエディット)。
+	pub preedit: String,
+}
+
+/// 逐次入力用のハングル合成器。
+///
+/// 1文字ずつ入力を受け取り、確定済みの接頭辞と入力中の音節を保持する。
+/// ローマ字入力では常に `committed() + preedit()` が `HangulConverter::convert(input())` と一致する。
+pub struct Composer {
+	/// 入力方式ごとの合成状態。
+	engine: Engine,
+}
+
+/// 入力方式ごとの合成状態。
+enum Engine {
+	/// ローマ字入力。
+	Romaja(Box<RomajaComposer>),
+	/// 字母配列入力。
+	Keyboard(KeyboardComposer),
+}
+
+impl Composer {
+	/// ローマ字入力の合成器を作成する。
+	pub fn new() -> Self {
+		Self::with_scheme(InputScheme::Romaja)
+	}
+
+	/// 変換器を指定してローマ字入力の合成器を作成する。
+	///
+	/// `Arc<HangulConverter>` を渡すと変換器を他の合成器と共有する。
+	pub fn with_converter(converter: impl Into<Arc<HangulConverter>>) -> Self {
+		Self {
+			engine: Engine::Romaja(Box::new(RomajaComposer::new(converter.into()))),
+		}
+	}
+
+	/// 入力方式を指定して合成器を作成する。
+	pub fn with_scheme(scheme: InputScheme) -> Self {
+		match scheme.layout() {
+			None => Self::with_converter(HangulConverter::shared(scheme, ConvertOptions::default())),
+			Some(layout) => Self {
+				engine: Engine::Keyboard(KeyboardComposer::new(layout)),
+			},
+		}
+	}
+
+	/// 入力として受け付けるキー (US配列の文字、大文字・記号 = Shift) か判定する。
+	///
+	/// ローマ字入力では英字とテーブルのキー・区切りに含まれる文字 (' や ^ など)、
+	/// 入力中であれば音節区切りの記号も受け付ける。数字は入力が空か数字のみの間だけ受け付ける
+	/// (数詞の候補用。それ以外の数字キーは候補の選択に使う)。
+	/// 字母配列入力では配列に割り当てのあるキーのみ。
+	pub fn is_input_key(&self, c: char) -> bool {
+		match &self.engine {
+			Engine::Romaja(e) => {
+				e.converter.is_key_char(c)
+					|| (!e.input.is_empty() && c == e.converter.options.syllable_break.break_char())
+					|| (c.is_ascii_digit() && e.raw.iter().all(char::is_ascii_digit))
+			}
+			Engine::Keyboard(e) => e.layout().is_layout_key(c),
+		}
+	}
+
+	/// 1文字 (キー) を追加する。
+	///
+	/// ローマ字入力ではスペースは `convert` と同じく音節区切り (2つで実際のスペース) として扱う。
+	pub fn push(&mut self, c: char) -> ComposerUpdate {
+		match &mut self.engine {
+			Engine::Romaja(e) => e.push(c),
+			Engine::Keyboard(e) => e.push(c),
+		}
+	}
+
+	/// 最後に入力した文字を取り消し、確定済みの変化と新しいプリエディットを返す。
+	///
+	/// 入力が空の場合はNoneを返す。取り消した文字によって確定した部分は未確定に戻り、
+	/// `retracted` で報告する (`committed` は常に空)。
+	pub fn pop(&mut self) -> Option<ComposerUpdate> {
+		match &mut self.engine {
+			Engine::Romaja(e) => e.pop(),
+			Engine::Keyboard(e) => e.pop(),
+		}
+	}
+
+	/// 未確定部分を確定して返し、状態をリセットする。
+	pub fn commit(&mut self) -> String {
+		let preedit = self.preedit();
+		self.clear();
+		preedit
+	}
+
+	/// 状態をリセットする。
+	pub fn clear(&mut self) {
+		match &mut self.engine {
+			Engine::Romaja(e) => e.clear(),
+			Engine::Keyboard(e) => e.clear(),
+		}
+	}
+
+	/// 確定済みの変換結果。
+	pub fn committed(&self) -> &str {
+		match &self.engine {
+			Engine::Romaja(e) => &e.committed,
+			Engine::Keyboard(e) => e.committed(),
+		}
+	}
+
+	/// 未確定の入力中文字列を返す。
+	pub fn preedit(&self) -> String {
+		match &self.engine {
+			Engine::Romaja(e) => e.preedit(),
+			Engine::Keyboard(e) => e.preedit(),
+		}
+	}
+
+	/// 確定済みと未確定を合わせた変換結果を返す。
+	pub fn text(&self) -> String {
+		let mut text = self.committed().to_string();
+		text.push_str(&self.preedit());
+		text
+	}
+
+	/// 入力全体の変換候補をスコア順に最大 `limit` 件返す。
+	///
+	/// 先頭は常に `text()` と同じ。字母配列入力では区切り方が一意なので候補は1件。
+	/// ローマ字入力で入力が数字で始まる場合は、先頭の次に数詞の読み (123 → 백이십삼,
+	/// 3gae → 세 개) の候補をスコア 0 で加える。
+	pub fn candidates(&self, limit: usize) -> Vec<Candidate> {
+		match &self.engine {
+			Engine::Romaja(e) => {
+				let (start, prefix) = e.candidate_prefix();
+				let mut candidates = e.converter.candidates_chars(&e.input[start..], prefix, limit);
+				let numerals = e.numeral_candidates().into_iter().map(|text| Candidate { text, score: 0 });
+				let at = candidates.len().min(1);
+				candidates.splice(at..at, numerals);
+				let mut seen = HashSet::new();
+				candidates.retain(|c| seen.insert(c.text.clone()));
+				candidates.truncate(limit);
+				candidates
+			}
+			Engine::Keyboard(_) => vec![Candidate {
+				text: self.text(),
+				score: 0,
+			}]
+			.into_iter()
+			.take(limit)
+			.collect(),
+		}
+	}
+
+	/// 入力全体が子音1つなら、その子音を互換字母で返す (記号パレット用)。
+	pub fn consonant(&self) -> Option<char> {
+		match &self.engine {
+			Engine::Romaja(e) => e.converter.consonant(&e.input()),
+			Engine::Keyboard(_) => {
+				let text = self.text();
+				let mut chars = text.chars();
+				match (chars.next(), chars.next()) {
+					(Some(c), None) if jamo::Cho::from_compat(c).is_some() => Some(c),
+					_ => None,
+				}
 			}
+		}
+	}
+
+	/// 入力された文字列 (ローマ字またはキー) を返す。
+	pub fn input(&self) -> String {
+		match &self.engine {
+			Engine::Romaja(e) => e.input(),
+			Engine::Keyboard(e) => e.input().to_string(),
+		}
+	}
+
+	/// 入力が空か判定する。
+	pub fn is_empty(&self) -> bool {
+		match &self.engine {
+			Engine::Romaja(e) => e.input.is_empty(),
+			Engine::Keyboard(e) => e.is_empty(),
+		}
+	}
+}
+
+/// ローマ字入力の合成状態。
+///
+/// 後続の入力で変化しなくなった音節から順に確定するため、
+/// バッファ全体を毎回変換し直す必要がない。
+/// 常に `committed + preedit()` は `HangulConverter::convert(input())` と一致する。
+struct RomajaComposer {
+	/// ハングル変換器 (共有可能)。
+	converter: Arc<HangulConverter>,
+	/// 入力されたローマ字 (入力どおり)。
+	raw: Vec<char>,
+	/// 大文字の扱いに従って変換用にした入力 (`raw` と1文字ずつ対応)。
+	input: Vec<char>,
+	/// 未確定部分の開始位置 (`input` のインデックス)。
+	start: usize,
+	/// 確定済みの変換結果。
+	committed: String,
+	/// 各入力文字の直前の状態 (`start`, `committed` の長さ)。pop で復元する。
+	history: Vec<(usize, usize)>,
+}
 
-			let substr: String = chars[pos..pos + len].iter().collect();
-			if let Some(&idx) = map.get(substr.as_str()) {
-				return Some((idx, len));
+impl RomajaComposer {
+	fn new(converter: Arc<HangulConverter>) -> Self {
+		Self {
+			converter,
+			raw: Vec::new(),
+			input: Vec::new(),
+			start: 0,
+			committed: String::new(),
+			history: Vec::new(),
+		}
+	}
+
+	/// ローマ字を1文字追加する。
+	///
+	/// 区切りとスペースは `convert` と同じく扱う。
+	fn push(&mut self, c: char) -> ComposerUpdate {
+		let committed_len = self.committed.len();
+		let break_char = self.converter.options.syllable_break.break_char();
+
+		self.history.push((self.start, self.committed.len()));
+		self.raw.push(c);
+		self.input.push(c);
+		let stable_end = self.refold();
+		let c = self.input[self.input.len() - 1];
+
+		if c == break_char || c == ' ' {
+			// 区切り → 直前の音節群をすべて確定。
+			let group = &self.input[self.start..self.input.len() - 1];
+			self.converter.convert_chars(group, &mut self.committed);
+			self.start = self.input.len();
+
+			if c != break_char {
+				// 区切りが記号ならスペースはそのまま確定。
+				self.committed.push(' ');
+			} else {
+				// 区切り2つごとに区切り文字そのものを確定。
+				let breaks = self.input.iter().rev().take_while(|&&ch| ch == break_char).count();
+				if breaks % 2 == 0 {
+					self.committed.push(break_char);
+				}
+			}
+		} else {
+			// 後続の入力で変化しない単位を確定。
+			let window = self.converter.stable_window();
+			while self.start + window <= stable_end {
+				self.start = self.converter.convert_unit(&self.input, self.start, &mut self.committed);
 			}
 		}
 
-		None
+		ComposerUpdate {
+			retracted: String::new(),
+			committed: self.committed[committed_len..].to_string(),
+			preedit: self.preedit(),
+		}
+	}
+
+	/// 最後に入力した文字を取り消し、未確定に戻した部分と新しいプリエディットを返す。
+	fn pop(&mut self) -> Option<ComposerUpdate> {
+		let (start, committed_len) = self.history.pop()?;
+		self.raw.pop();
+		self.input.pop();
+		if !self.raw.is_empty() {
+			self.refold();
+		}
+		self.start = start;
+		let retracted = self.committed.split_off(committed_len);
+		Some(ComposerUpdate {
+			retracted,
+			committed: String::new(),
+			preedit: self.preedit(),
+		})
+	}
+
+	/// 状態をリセットする。
+	fn clear(&mut self) {
+		self.raw.clear();
+		self.input.clear();
+		self.start = 0;
+		self.committed.clear();
+		self.history.clear();
+	}
+
+	/// 未確定の入力中文字列を返す。
+	fn preedit(&self) -> String {
+		let mut preedit = String::new();
+		self.converter.convert_chars(&self.input[self.start..], &mut preedit);
+		preedit
+	}
+
+	/// 変換候補で区切り方を変えない確定済みの部分 (入力の位置, その位置までの変換結果) を返す。
+	///
+	/// 末尾 [`CANDIDATE_WINDOW`] 文字より前の入力時点で確定していた部分を使い、
+	/// 候補の列挙で入力全体を変換し直さないようにする。その位置から区切りが続く場合は
+	/// 区切りの数で出力が変わるため、それより前の時点を使う。
+	fn candidate_prefix(&self) -> (usize, &str) {
+		let break_char = self.converter.options.syllable_break.break_char();
+		let window_start = self.input.len().saturating_sub(CANDIDATE_WINDOW);
+		self.history[..window_start.min(self.history.len())]
+			.iter()
+			.rev()
+			.find(|&&(start, _)| !matches!(self.input.get(start), Some(&c) if c == break_char || c == ' '))
+			.map_or((0, ""), |&(start, len)| (start, &self.committed[..len]))
+	}
+
+	/// 入力されたローマ字を返す。
+	fn input(&self) -> String {
+		self.raw.iter().collect()
+	}
+
+	/// 先頭の数字の読みの候補を返す (数字で始まらなければ空)。
+	///
+	/// 数字に続く入力を変換した結果を助数詞として扱う (3gae → 세 개)。
+	/// 助数詞が完成形ハングルにならない場合は候補なし。
+	fn numeral_candidates(&self) -> Vec<String> {
+		let digits = self.raw.iter().take_while(|c| c.is_ascii_digit()).count();
+		let Some(n) = numeral::parse(&self.raw[..digits].iter().collect::<String>()) else {
+			return Vec::new();
+		};
+		let counter = self.converter.convert(&self.raw[digits..].iter().collect::<String>());
+		let counter = counter.trim();
+		if !counter.chars().all(|c| jamo::decompose(c).is_some()) {
+			return Vec::new();
+		}
+		numeral::readings(n, counter)
 	}
 
-	/// 初声・中声・終声からハングル文字を合成する。
-	fn compose(&self, cho: u32, jung: u32, jong: u32) -> char {
-		let code = 0xAC00 + (cho * 21 + jung) * 28 + jong;
-		char::from_u32(code).unwrap_or('?')
+	/// 末尾の英字の並びを大文字の扱いに従って変換用の文字にし直す。
+	///
+	/// 確定してよい範囲の終わりを返す。すべて大文字の並びは後続の入力で
+	/// 英字のままにするかが変わりうるため、大文字をそのまま出力する設定ではその開始位置まで。
+	fn refold(&mut self) -> usize {
+		let len = self.raw.len();
+		let start = run_start(&self.raw, len);
+		self.input[start..].copy_from_slice(&self.raw[start..]);
+		self.converter.fold_run(&mut self.input[start..]);
+
+		let run = &self.raw[start..];
+		let undecided = self.converter.options.case == CasePolicy::PreserveUppercase
+			&& run.iter().all(|c| c.is_alphabetic() && c.is_uppercase());
+		if undecided { start } else { len }
 	}
 }
 
-impl Default for HangulConverter {
+impl Default for Composer {
 	fn default() -> Self {
 		Self::new()
 	}
@@ -1025,6 +2071,835 @@ mod tests {
 		assert_eq!(c.convert(&buffer), "");
 	}
 
+	// ==================== 逐次合成テスト ====================
+
+	/// Composerで1文字ずつ入力し、各段階の変換結果を返す。
+	fn simulate_composer(input: &str) -> Vec<String> {
+		let mut composer = Composer::new();
+		input.chars().map(|ch| {
+			composer.push(ch);
+			composer.text()
+		}).collect()
+	}
+
+	#[test]
+	fn test_composer_matches_simulate_typing() {
+		let inputs = [
+			"han", "hangu", "han gug", "gugeo", "an nyeong  ha se yo",
+			"gam sa hab ni da", "test ga na da", "a    beu", "  ga",
+			"wol hwa su mog geum to il", "gwaen chanh a yo",
+			"hangugeoreulgongbuhagoissseubnida", "dae han min gug",
+			"ANNYEONG", "123 !@# fxz",
+		];
+		for input in inputs {
+			assert_eq!(simulate_composer(input), simulate_typing(input), "input: {}", input);
+		}
+	}
+
+	#[test]
+	fn test_composer_matches_convert_random() {
+		// 疑似乱数で生成した入力のすべての接頭辞で convert と一致すること。
+		let alphabet: Vec<char> = "gknrdtlmbpsjchaeiouywx ".chars().collect();
+		for options in [ConvertOptions::default(), ConvertOptions { compat_jamo: true, ..ConvertOptions::default() }] {
+			let c = HangulConverter::with_options(options);
+			let mut seed: u32 = 12345;
+			for _ in 0..200 {
+				let mut composer = Composer::with_converter(HangulConverter::with_options(options));
+				let mut buffer = String::new();
+				for _ in 0..40 {
+					seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+					let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+					buffer.push(ch);
+					composer.push(ch);
+					assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+				}
+				// 取り消しでも一致すること。
+				while composer.pop().is_some() {
+					buffer.pop();
+					assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_composer_commit_on_space() {
+		let mut composer = Composer::new();
+		for ch in "han".chars() {
+			let update = composer.push(ch);
+			assert_eq!(update.committed, "");
+		}
+		assert_eq!(composer.preedit(), "한");
+
+		// スペースで直前の音節が確定。
+		let update = composer.push(' ');
+		assert_eq!(update.committed, "한");
+		assert_eq!(update.preedit, "");
+
+		composer.push('g');
+		let update = composer.push('u');
+		assert_eq!(update.preedit, "구");
+		assert_eq!(composer.committed(), "한");
+
+		// 2つ目のスペースで実際のスペースが確定。
+		let mut composer = Composer::new();
+		composer.push('a');
+		assert_eq!(composer.push(' ').committed, "아");
+		assert_eq!(composer.push(' ').committed, " ");
+	}
+
+	#[test]
+	fn test_composer_commit_long_input() {
+		// 区切りなしの長い入力でも、先読み範囲外の音節は確定する。
+		let mut composer = Composer::new();
+		for ch in "hangugeoreulgongbu".chars() {
+			composer.push(ch);
+		}
+		assert!(!composer.committed().is_empty());
+		assert!(composer.preedit().chars().count() < composer.text().chars().count());
+		assert_eq!(composer.text(), HangulConverter::new().convert("hangugeoreulgongbu"));
+	}
+
+	#[test]
+	fn test_composer_pop_and_commit() {
+		let mut composer = Composer::new();
+		for ch in "han ".chars() {
+			composer.push(ch);
+		}
+		assert_eq!(composer.committed(), "한");
+
+		// 区切りスペースを取り消すと音節が未確定に戻る。
+		let update = composer.pop().unwrap();
+		assert_eq!(update.retracted, "한");
+		assert_eq!(update.committed, "");
+		assert_eq!(update.preedit, "한");
+		assert_eq!(composer.committed(), "");
+		let update = composer.pop().unwrap();
+		assert_eq!(update.retracted, "");
+		assert_eq!(update.preedit, "하");
+
+		assert_eq!(composer.commit(), "하");
+		assert!(composer.is_empty());
+		assert_eq!(composer.pop(), None);
+	}
+
+	#[test]
+	fn test_composer_with_scheme() {
+		let mut composer = Composer::with_scheme(InputScheme::Dubeolsik);
+		for ch in "gksrmf".chars() {
+			composer.push(ch);
+		}
+		assert_eq!(composer.committed(), "한");
+		assert_eq!(composer.preedit(), "글");
+		assert_eq!(composer.input(), "gksrmf");
+
+		let mut composer = Composer::with_scheme(InputScheme::Romaja);
+		for ch in "han gul".chars() {
+			composer.push(ch);
+		}
+		assert_eq!(composer.text(), "한굴");
+
+		let mut composer = Composer::with_scheme(InputScheme::Sebeolsik390);
+		for ch in "mfskgw".chars() {
+			composer.push(ch);
+		}
+		assert_eq!(composer.text(), "한글");
+	}
+
+	#[test]
+	fn test_convert_candidates() {
+		let c = HangulConverter::new();
+		let texts = |input: &str| -> Vec<String> {
+			c.convert_candidates(input, 10).into_iter().map(|c| c.text).collect()
+		};
+		assert_eq!(texts("gangi"), ["간기", "강이"]);
+		assert_eq!(texts("hangeul"), ["한글", "항을"]);
+		assert_eq!(texts("bangeul"), ["반글", "방을"]);
+		assert_eq!(texts("ga"), ["가"]);
+		assert_eq!(texts("han geul  gangi")[..2], ["한글 간기", "한글 강이"]);
+
+		let candidates = c.convert_candidates("gangi", 10);
+		assert_eq!(candidates[0].score, 0);
+		assert_eq!(candidates[1].score, -1);
+		assert_eq!(c.convert_candidates("gangi", 1).len(), 1);
+		assert!(c.convert_candidates("gangi", 0).is_empty());
+	}
+
+	#[test]
+	fn test_convert_candidates_top_matches_convert() {
+		let alphabet: Vec<char> = "gnlkaeiou ".chars().collect();
+		let c = HangulConverter::new();
+		let mut seed: u32 = 777;
+		for _ in 0..500 {
+			let mut input = String::new();
+			for _ in 0..12 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				input.push(alphabet[(seed >> 16) as usize % alphabet.len()]);
+			}
+			let candidates = c.convert_candidates(&input, 5);
+			assert_eq!(candidates[0].text, c.convert(&input), "input: {:?}", input);
+			assert!(candidates.windows(2).all(|w| w[0].score >= w[1].score), "input: {:?}", input);
+			assert!(candidates[1..].iter().all(|c| c.score < 0), "input: {:?}", input);
+		}
+	}
+
+	#[test]
+	fn test_convert_candidates_window() {
+		let c = HangulConverter::new();
+		// 区切り方を変えるのは末尾の範囲のみ。それより前は `convert` と同じ。
+		let head = "gangi ".repeat(20);
+		let candidates = c.convert_candidates(&format!("{}gangi", head), 10);
+		assert_eq!(candidates[0].text, c.convert(&format!("{}gangi", head)));
+		assert!(candidates.iter().any(|candidate| candidate.text == format!("{}강이", c.convert(&head))));
+		let fixed = c.convert(&"gangi ".repeat(14));
+		assert!(candidates.iter().all(|candidate| candidate.text.starts_with(&fixed)));
+
+		// 区切りのない長い入力でも候補の数は上限まで。
+		let long = "gangi".repeat(400);
+		let candidates = c.convert_candidates(&long, 9);
+		assert_eq!(candidates.len(), 9);
+		assert_eq!(candidates[0].text, c.convert(&long));
+	}
+
+	#[test]
+	fn test_composer_candidates_match_convert_candidates() {
+		let alphabet: Vec<char> = "gnlkaeiou -".chars().collect();
+		for options in [
+			ConvertOptions::default(),
+			ConvertOptions { syllable_break: SyllableBreak::Char('-'), ..ConvertOptions::default() },
+		] {
+			let c = Arc::new(HangulConverter::with_options(options));
+			let mut seed: u32 = 4242;
+			for _ in 0..100 {
+				let mut composer = Composer::with_converter(Arc::clone(&c));
+				let mut input = String::new();
+				for _ in 0..80 {
+					seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+					let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+					input.push(ch);
+					composer.push(ch);
+				}
+				assert_eq!(composer.candidates(5), c.convert_candidates(&input, 5), "input: {:?}", input);
+			}
+		}
+	}
+
+	#[test]
+	fn test_composer_candidates() {
+		let mut composer = Composer::new();
+		for c in "gangi".chars() {
+			composer.push(c);
+		}
+		let texts: Vec<String> = composer.candidates(5).into_iter().map(|c| c.text).collect();
+		assert_eq!(texts, ["간기", "강이"]);
+
+		let mut composer = Composer::with_scheme(InputScheme::Dubeolsik);
+		for c in "rksrl".chars() {
+			composer.push(c);
+		}
+		assert_eq!(composer.candidates(5).len(), 1);
+	}
+
+	#[test]
+	fn test_syllable_break_char() {
+		let c = HangulConverter::with_options(ConvertOptions {
+			syllable_break: SyllableBreak::Char('-'),
+			..ConvertOptions::default()
+		});
+		assert_eq!(c.convert("han-gug-eo"), "한국어");
+		assert_eq!(c.convert("an-nyeong ha-se-yo"), "안녕 하세요");
+		assert_eq!(c.convert("hangug  eo"), "한국  어");
+		assert_eq!(c.convert("han--gug"), "한-국");
+		assert_eq!(c.convert("han---gug"), "한-국");
+
+		// 区切りはテーブルの区切り文字より優先される。
+		let c = HangulConverter::with_scheme(InputScheme::McCuneReischauer, ConvertOptions {
+			syllable_break: SyllableBreak::Char('\''),
+			..ConvertOptions::default()
+		});
+		assert_eq!(c.convert("k'a"), "k아");
+	}
+
+	#[test]
+	fn test_syllable_break_names() {
+		assert_eq!(SyllableBreak::from_name("space"), Some(SyllableBreak::Space));
+		assert_eq!(SyllableBreak::from_name("."), Some(SyllableBreak::Char('.')));
+		assert_eq!(SyllableBreak::from_name("a"), None);
+		assert_eq!(SyllableBreak::from_name("--"), None);
+		for b in [SyllableBreak::Space, SyllableBreak::Char('\'')] {
+			assert_eq!(SyllableBreak::from_name(&b.name()), Some(b));
+		}
+	}
+
+	#[test]
+	fn test_composer_syllable_break_matches_convert() {
+		let alphabet: Vec<char> = "gnaeo- ".chars().collect();
+		let options = ConvertOptions {
+			syllable_break: SyllableBreak::Char('-'),
+			..ConvertOptions::default()
+		};
+		let c = HangulConverter::with_options(options);
+		let mut seed: u32 = 2024;
+		for _ in 0..200 {
+			let mut composer = Composer::with_converter(HangulConverter::with_options(options));
+			let mut buffer = String::new();
+			for _ in 0..30 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+				buffer.push(ch);
+				composer.push(ch);
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+
+		let mut composer = Composer::with_converter(HangulConverter::with_options(options));
+		assert!(!composer.is_input_key('-'));
+		composer.push('a');
+		assert!(composer.is_input_key('-'));
+	}
+
+	#[test]
+	fn test_composer_is_input_key() {
+		let composer = Composer::new();
+		assert!(composer.is_input_key('a'));
+		assert!(composer.is_input_key('A'));
+		assert!(!composer.is_input_key(';'));
+		assert!(!composer.is_input_key('\''));
+
+		let composer = Composer::with_scheme(InputScheme::McCuneReischauer);
+		assert!(composer.is_input_key('\''));
+		assert!(composer.is_input_key('^'));
+		assert!(!composer.is_input_key(';'));
+
+		let composer = Composer::with_scheme(InputScheme::SebeolsikFinal);
+		assert!(composer.is_input_key(';'));
+		assert!(composer.is_input_key('1'));
+		assert!(!composer.is_input_key(' '));
+	}
+
+	#[test]
+	fn test_input_scheme_names() {
+		for scheme in InputScheme::ALL {
+			assert_eq!(InputScheme::from_name(scheme.name()), Some(scheme));
+		}
+		assert_eq!(InputScheme::from_name("unknown"), None);
+		assert_eq!(InputScheme::default(), InputScheme::Romaja);
+	}
+
+	#[test]
+	fn test_compat_jamo_orphan_consonants() {
+		let c = HangulConverter::with_options(ConvertOptions { compat_jamo: true, ..ConvertOptions::default() });
+		assert_eq!(c.convert("k k k"), "ㅋㅋㅋ");
+		assert_eq!(c.convert("hh"), "ㅎㅎ");
+		assert_eq!(c.convert("g s"), "ㄱㅅ");
+		assert_eq!(c.convert("kk"), "ㄲ");
+		assert_eq!(c.convert("ch"), "ㅊ");
+		// 終声にしかない綴りは1字母。
+		assert_eq!(c.convert("ng"), "ㅇ");
+		assert_eq!(c.convert("gs"), "ㄳ");
+		assert_eq!(c.convert("lg"), "ㄺ");
+		assert_eq!(c.convert("bs"), "ㅄ");
+		// 母音が続けば通常どおり。
+		assert_eq!(c.convert("nga"), "ㄴ가");
+		assert_eq!(c.convert("hangk"), "항ㅋ");
+		assert_eq!(c.convert("a"), "아");
+		assert_eq!(c.convert("han gul"), "한굴");
+		// マッピングのない文字はそのまま。
+		assert_eq!(c.convert("x"), "x");
+	}
+
+	#[test]
+	fn test_custom_tables() {
+		let json = r#"{
+			"choseong": { "entries": { "q": "ㄲ" } },
+			"jungseong": { "entries": { "oo": "ㅜ", "ee": "ㅣ" } }
+		}"#;
+		let (tables, _) = RomajaTables::from_json(json).unwrap();
+		let c = HangulConverter::with_tables(tables, ConvertOptions::default());
+		assert_eq!(c.convert("qoom"), "꿈");
+		assert_eq!(c.convert("see"), "시");
+		assert_eq!(c.convert("han gug eo"), "한국어");
+
+		// 置換したテーブルにないキーは変換しない。
+		let json = r#"{ "jongseong": { "mode": "replace", "entries": { "n": "ㄴ" } } }"#;
+		let (tables, _) = RomajaTables::from_json(json).unwrap();
+		let c = HangulConverter::with_tables(tables, ConvertOptions::default());
+		assert_eq!(c.convert("han"), "한");
+		assert_eq!(c.convert("hag"), "하g");
+	}
+
+	#[test]
+	fn test_composer_with_long_custom_keys() {
+		// 標準より長いキーでも逐次入力の結果が一致すること。
+		let json = r#"{
+			"choseong": { "entries": { "kkk": "ㄲ" } },
+			"jungseong": { "entries": { "oooooooooo": "ㅜ" } },
+			"jongseong": { "entries": { "nnn": "ㄴ" } }
+		}"#;
+		let (tables, _) = RomajaTables::from_json(json).unwrap();
+		let c = HangulConverter::with_tables(tables.clone(), ConvertOptions::default());
+		let input = "kkkoooooooooonnnkkkooooooooookkkaoooooooooonnngoooooooooooo";
+		let mut composer = Composer::with_converter(HangulConverter::with_tables(tables, ConvertOptions::default()));
+		let mut buffer = String::new();
+		for ch in input.chars() {
+			buffer.push(ch);
+			composer.push(ch);
+			assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+		}
+	}
+
+	#[test]
+	fn test_mccune_reischauer() {
+		let c = HangulConverter::with_scheme(InputScheme::McCuneReischauer, ConvertOptions::default());
+		// 半月符・ASCII 代替・結合文字。
+		assert_eq!(c.convert("Chosŏn"), "조선");
+		assert_eq!(c.convert("choso^n"), "조선");
+		assert_eq!(c.convert("so\u{306}ul"), "서울");
+		assert_eq!(c.convert("Kyŏngju"), "경주");
+		assert_eq!(c.convert("hangŭl"), "한글");
+		assert_eq!(c.convert("u^i"), "의");
+		// アポストロフィの激音。
+		assert_eq!(c.convert("ch'a"), "차");
+		assert_eq!(c.convert("k'o"), "코");
+		assert_eq!(c.convert("t'ap"), "탑");
+		assert_eq!(c.convert("p'a"), "파");
+		assert_eq!(c.convert("kkach'i"), "까치");
+		assert_eq!(c.convert("kkoch'i"), "꼬치");
+		assert_eq!(c.convert("hak'a"), "하카");
+		assert_eq!(c.convert("puŏk'"), "부엌");
+		// 平音・有声音・濃音。
+		assert_eq!(c.convert("Pusan"), "부산");
+		assert_eq!(c.convert("Taegu"), "대구");
+		assert_eq!(c.convert("tchigae"), "찌개");
+		// 激音にならないアポストロフィは音節区切り。
+		assert_eq!(c.convert("Han'guk"), "한국");
+		assert_eq!(c.convert("hanguk"), "한국");
+		assert_eq!(c.convert("han'gug'ŏ"), "한국어");
+		// スペースの扱いは標準と同じ。
+		assert_eq!(c.convert("han guk  mal"), "한국 말");
+	}
+
+	#[test]
+	fn test_yale() {
+		let c = HangulConverter::with_scheme(InputScheme::Yale, ConvertOptions::default());
+		assert_eq!(c.convert("hankwuk"), "한국");
+		assert_eq!(c.convert("wu"), "우");
+		assert_eq!(c.convert("ey"), "에");
+		assert_eq!(c.convert("salam"), "사람");
+		assert_eq!(c.convert("chayk"), "책");
+		assert_eq!(c.convert("oy uy yey"), "외의예");
+		// 両唇音の後の u は ㅜ。
+		assert_eq!(c.convert("mul"), "물");
+		assert_eq!(c.convert("pul"), "불");
+		assert_eq!(c.convert("mues"), "무엇");
+		assert_eq!(c.convert("kulim"), "그림");
+		assert_eq!(c.convert("pwuekh"), "부엌");
+		// 形態音素的な綴り。
+		assert_eq!(c.convert("kaps"), "값");
+		assert_eq!(c.convert("epsta"), "없다");
+		assert_eq!(c.convert("ilkta"), "읽다");
+		assert_eq!(c.convert("kkoch"), "꽃");
+		assert_eq!(c.convert("ancta"), "앉다");
+		assert_eq!(c.convert("hankwuk  mal"), "한국 말");
+	}
+
+	#[test]
+	fn test_composer_yale_matches_convert() {
+		let alphabet: Vec<char> = "kcmpthnlsaeiouyw ".chars().collect();
+		let c = HangulConverter::with_scheme(InputScheme::Yale, ConvertOptions::default());
+		let mut seed: u32 = 9876;
+		for _ in 0..200 {
+			let mut composer = Composer::with_scheme(InputScheme::Yale);
+			let mut buffer = String::new();
+			for _ in 0..40 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+				buffer.push(ch);
+				composer.push(ch);
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+	}
+
+	#[test]
+	fn test_composer_mccune_reischauer_matches_convert() {
+		let alphabet: Vec<char> = "kgnrtlmpschaeiouyw'^ŏŭ ".chars().collect();
+		let c = HangulConverter::with_scheme(InputScheme::McCuneReischauer, ConvertOptions::default());
+		let mut seed: u32 = 54321;
+		for _ in 0..200 {
+			let mut composer = Composer::with_scheme(InputScheme::McCuneReischauer);
+			let mut buffer = String::new();
+			for _ in 0..40 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+				buffer.push(ch);
+				composer.push(ch);
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+	}
+
+	#[test]
+	fn test_archaic() {
+		let options = ConvertOptions { archaic: true, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		// 古語の字母を含む音節は結合型字母の列になる。
+		assert_eq!(c.convert("haan"), "\u{1112}\u{119E}\u{11AB}");
+		assert_eq!(c.convert("guz"), "\u{1100}\u{116E}\u{11EB}");
+		assert_eq!(c.convert("oyeo"), "\u{110B}\u{D7B0}");
+		assert_eq!(c.convert("ldaa"), "\u{A966}\u{119E}");
+		assert_eq!(c.convert("gabd"), "\u{1100}\u{1161}\u{D7E3}");
+		assert_eq!(c.convert("sgaa"), "\u{112D}\u{119E}");
+		// 現代語の音節は完成形のまま。
+		assert_eq!(c.convert("saram"), "사람");
+		assert_eq!(c.convert("han gug eo"), "한국어");
+
+		// 無効の場合は古語の綴りを受け付けない。
+		assert_eq!(HangulConverter::new().convert("zaa"), "z아아");
+
+		let options = ConvertOptions { archaic: true, compat_jamo: true, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		assert_eq!(c.convert("z x q"), "ㅿㆁㆆ");
+	}
+
+	#[test]
+	fn test_composer_archaic_matches_convert() {
+		let alphabet: Vec<char> = "gnbsdlmhzxqvfaeoiuy ".chars().collect();
+		let options = ConvertOptions { archaic: true, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		let mut seed: u32 = 1443;
+		for _ in 0..200 {
+			let mut composer = Composer::with_converter(HangulConverter::with_options(options));
+			let mut buffer = String::new();
+			for _ in 0..40 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+				buffer.push(ch);
+				composer.push(ch);
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+	}
+
+	#[test]
+	fn test_output_form() {
+		let convert = |form, input| {
+			let options = ConvertOptions { form, ..ConvertOptions::default() };
+			HangulConverter::with_options(options).convert(input)
+		};
+		assert_eq!(convert(OutputForm::Nfc, "han gug eo"), "한국어");
+		assert_eq!(
+			convert(OutputForm::Nfd, "han gug eo"),
+			"\u{1112}\u{1161}\u{11AB}\u{1100}\u{116E}\u{11A8}\u{110B}\u{1165}"
+		);
+		assert_eq!(convert(OutputForm::Compat, "ilg go  gwaja"), "ㅇㅣㄺㄱㅗ ㄱㅘㅈㅏ");
+		for form in OutputForm::ALL {
+			let text = convert(form, "an nyeong ha se yo");
+			assert_eq!(normalize::normalize(&text, OutputForm::Nfc), "안녕하세요", "form: {:?}", form);
+		}
+
+		let options = ConvertOptions { form: OutputForm::Nfd, ..ConvertOptions::default() };
+		let candidates = HangulConverter::with_options(options).convert_candidates("gangi", 2);
+		assert_eq!(candidates[1].text, "\u{1100}\u{1161}\u{11BC}\u{110B}\u{1175}");
+	}
+
+	#[test]
+	fn test_composer_output_form_matches_convert() {
+		let alphabet: Vec<char> = "gnbsdlmhaeoiuyw ".chars().collect();
+		let options = ConvertOptions { form: OutputForm::Nfd, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		let mut seed: u32 = 3131;
+		for _ in 0..200 {
+			let mut composer = Composer::with_converter(HangulConverter::with_options(options));
+			let mut buffer = String::new();
+			for _ in 0..40 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+				buffer.push(ch);
+				composer.push(ch);
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+	}
+
+	#[test]
+	fn test_convert_with_spans() {
+		let c = HangulConverter::new();
+		let spans = c.convert_with_spans("Hangug  eo k1", None);
+		let summary: Vec<(&str, Range<usize>, SpanSource)> =
+			spans.iter().map(|s| (s.text.as_str(), s.chars.clone(), s.source)).collect();
+		assert_eq!(
+			summary,
+			vec![
+				("한", 0..3, SpanSource::Converted),
+				("국", 3..6, SpanSource::Converted),
+				(" ", 6..8, SpanSource::Passthrough),
+				("어", 8..10, SpanSource::Converted),
+				("k", 11..12, SpanSource::Passthrough),
+				("1", 12..13, SpanSource::Passthrough),
+			]
+		);
+
+		// バイト範囲は元の入力 (マルチバイト文字を含む) の位置。
+		let c = HangulConverter::with_scheme(InputScheme::McCuneReischauer, ConvertOptions::default());
+		let input = "Chosŏn";
+		let spans = c.convert_with_spans(input, None);
+		assert_eq!(spans.len(), 2);
+		assert_eq!(spans[1].chars, 3..6);
+		assert_eq!(&input[spans[1].bytes.clone()], "sŏn");
+	}
+
+	#[test]
+	fn test_convert_with_spans_user_dict() {
+		let dir = tempfile::tempdir().unwrap();
+		let path = dir.path().join("user_dict.json");
+		std::fs::write(&path, r#"{ "entries": { "addr": "서울시 강남구" } }"#).unwrap();
+		let dict = UserDict::load(&path);
+
+		let c = HangulConverter::new();
+		let spans = c.convert_with_spans("Addr", Some(&dict));
+		assert_eq!(
+			spans,
+			vec![Span {
+				text: "서울시 강남구".to_string(),
+				bytes: 0..4,
+				chars: 0..4,
+				source: SpanSource::UserDict,
+			}]
+		);
+		// 辞書に一致しなければ通常の変換。
+		assert_eq!(c.convert_with_spans("han", Some(&dict))[0].source, SpanSource::Converted);
+		for input in ["addr han", "hangugeo", "addr1"] {
+			let text: String = c.convert_with_spans(input, Some(&dict)).iter().map(|s| s.text.as_str()).collect();
+			assert_eq!(text, c.convert(input), "input: {:?}", input);
+		}
+	}
+
+	#[test]
+	fn test_convert_with_spans_matches_convert() {
+		let alphabet: Vec<char> = "gnbsdlmhaeoiuyw-1 ".chars().collect();
+		for options in [
+			ConvertOptions::default(),
+			ConvertOptions { compat_jamo: true, syllable_break: SyllableBreak::Char('-'), ..ConvertOptions::default() },
+		] {
+			let c = HangulConverter::with_options(options);
+			let mut seed: u32 = 4242;
+			for _ in 0..300 {
+				let mut input = String::new();
+				for _ in 0..20 {
+					seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+					input.push(alphabet[(seed >> 16) as usize % alphabet.len()]);
+				}
+				let spans = c.convert_with_spans(&input, None);
+				let text: String = spans.iter().map(|s| s.text.as_str()).collect();
+				assert_eq!(text, c.convert(&input), "input: {:?}", input);
+				// 範囲は重ならず順に並ぶ。
+				for pair in spans.windows(2) {
+					assert!(pair[0].chars.end <= pair[1].chars.start, "input: {:?}", input);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_convert_into() {
+		let c = HangulConverter::new();
+		let mut out = String::from("> ");
+		c.convert_into("Han gug eo  k''", &mut out).unwrap();
+		assert_eq!(out, format!("> {}", c.convert("Han gug eo  k''")));
+
+		// 書き込み先のエラーはそのまま返す。
+		struct Failing;
+		impl fmt::Write for Failing {
+			fn write_str(&mut self, _: &str) -> fmt::Result {
+				Err(fmt::Error)
+			}
+		}
+		assert!(c.convert_into("han", &mut Failing).is_err());
+	}
+
+	#[test]
+	fn test_shared_converter() {
+		let options = ConvertOptions::default();
+		let a = HangulConverter::shared(InputScheme::Yale, options);
+		let b = HangulConverter::shared(InputScheme::Yale, options);
+		assert!(Arc::ptr_eq(&a, &b));
+		let compat = ConvertOptions { compat_jamo: true, ..options };
+		assert!(!Arc::ptr_eq(&a, &HangulConverter::shared(InputScheme::Yale, compat)));
+		assert_eq!(a.convert("hankwuk"), "한국");
+
+		// 共有した変換器で作成した合成器は互いに独立。
+		let mut first = Composer::with_converter(Arc::clone(&a));
+		let mut second = Composer::with_converter(a);
+		first.push('h');
+		second.push('m');
+		assert_eq!(first.input(), "h");
+		assert_eq!(second.input(), "m");
+	}
+
+	#[test]
+	fn test_case_policy() {
+		let convert = |case, input| {
+			let options = ConvertOptions { case, ..ConvertOptions::default() };
+			HangulConverter::with_options(options).convert(input)
+		};
+		// 既定では大文字を小文字として扱う。
+		assert_eq!(convert(CasePolicy::Ignore, "Gae  KTX"), "개 ktx");
+
+		// Shift: 濃音・ㅣ系の二重母音。
+		assert_eq!(convert(CasePolicy::Shift, "Gae"), "깨");
+		assert_eq!(convert(CasePolicy::Shift, "Dal Bang Sal Ja"), "딸빵쌀짜");
+		assert_eq!(convert(CasePolicy::Shift, "gAe gEo gE"), "걔겨계");
+		// 2ボル式のキー位置 (O → ㅒ, P → ㅖ) ではなく綴りに従う。
+		assert_eq!(convert(CasePolicy::Shift, "gO Pang"), "교빵");
+		assert_eq!(convert(CasePolicy::Shift, "iSda"), "있다");
+		assert_eq!(convert(CasePolicy::Shift, "Han"), "한");
+
+		// PreserveUppercase: すべて大文字の単語は英字のまま。
+		assert_eq!(convert(CasePolicy::PreserveUppercase, "KTX reul ta go"), "KTX를타고");
+		assert_eq!(convert(CasePolicy::PreserveUppercase, "SNS-e"), "SNS-에");
+		assert_eq!(convert(CasePolicy::PreserveUppercase, "Seoul A"), "서울아");
+		assert_eq!(convert(CasePolicy::PreserveUppercase, "HanGUG"), "한국");
+	}
+
+	#[test]
+	fn test_composer_case_policy_matches_convert() {
+		let alphabet: Vec<char> = "gdbsjnhaeouiGDSKTXAEO- ".chars().collect();
+		for case in CasePolicy::ALL {
+			let options = ConvertOptions { case, ..ConvertOptions::default() };
+			let c = HangulConverter::with_options(options);
+			let mut seed: u32 = 1616;
+			for _ in 0..200 {
+				let mut composer = Composer::with_converter(HangulConverter::shared(InputScheme::Romaja, options));
+				let mut buffer = String::new();
+				for _ in 0..40 {
+					seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+					if seed >> 28 == 0 && !buffer.is_empty() {
+						buffer.pop();
+						composer.pop();
+					} else {
+						let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+						buffer.push(ch);
+						composer.push(ch);
+					}
+					assert_eq!(composer.text(), c.convert(&buffer), "case: {:?}, input: {:?}", case, buffer);
+					assert_eq!(composer.input(), buffer);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn test_symbols() {
+		let options = ConvertOptions { symbols: true, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		assert_eq!(c.convert("{{hangug}}  [[annyeong]]"), "『한국』 「안녕」");
+		assert_eq!(c.convert("<<dongmu>>  [<i>]"), "《동무》 〈이〉");
+		assert_eq!(c.convert("han..geul...  **~~\"\""), "한·글… ※～〃");
+		assert_eq!(c.convert("\\(\\1\\)\\!"), "（１）！");
+		// 綴りにならない記号はそのまま。
+		assert_eq!(c.convert("a.ba,c?"), "아.바,c?");
+		assert!(c.is_key_char('<') && c.is_key_char('\\'));
+
+		// 記号入力が無効なら変換しない。
+		let c = HangulConverter::new();
+		assert_eq!(c.convert("{{han}}"), "{{한}}");
+		assert!(!c.is_key_char('<'));
+
+		// テーブルの symbols で綴りを追加・上書きできる。
+		let json = r#"{ "symbols": { "entries": { "->": "→", "..": "‥" } } }"#;
+		let (tables, _) = RomajaTables::from_json(json).unwrap();
+		let c = HangulConverter::with_tables(tables, options);
+		assert_eq!(c.convert("a->ba..c"), "아→바‥c");
+	}
+
+	#[test]
+	fn test_symbols_spans_and_composer() {
+		let options = ConvertOptions { symbols: true, ..ConvertOptions::default() };
+		let c = HangulConverter::with_options(options);
+		let spans = c.convert_with_spans("han...", None);
+		assert_eq!(spans[1].text, "…");
+		assert_eq!(spans[1].chars, 3..6);
+		assert_eq!(spans[1].source, SpanSource::Symbol);
+
+		let alphabet: Vec<char> = "gnhaeo.<>[]{}*~\\!\" ".chars().collect();
+		let mut seed: u32 = 1717;
+		for _ in 0..200 {
+			let mut composer = Composer::with_converter(HangulConverter::shared(InputScheme::Romaja, options));
+			let mut buffer = String::new();
+			for _ in 0..40 {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+				if seed >> 28 == 0 && !buffer.is_empty() {
+					buffer.pop();
+					composer.pop();
+				} else {
+					let ch = alphabet[(seed >> 16) as usize % alphabet.len()];
+					buffer.push(ch);
+					composer.push(ch);
+				}
+				assert_eq!(composer.text(), c.convert(&buffer), "input: {:?}", buffer);
+			}
+		}
+	}
+
+	#[test]
+	fn test_composer_numeral_candidates() {
+		let texts = |input: &str| {
+			let mut composer = Composer::new();
+			for c in input.chars() {
+				assert!(composer.is_input_key(c), "input: {:?}, key: {:?}", input, c);
+				composer.push(c);
+			}
+			composer.candidates(10).into_iter().map(|c| c.text).collect::<Vec<_>>()
+		};
+		assert_eq!(texts("13"), vec!["13", "십삼", "열셋"]);
+		assert_eq!(texts("120000000"), vec!["120000000", "일억 이천만", "1억 2천만"]);
+		assert_eq!(texts("3gae"), vec!["3개", "세 개"]);
+		assert_eq!(texts("2myeong"), vec!["2명", "두 명"]);
+		assert_eq!(texts("6wol"), vec!["6월", "유월"]);
+		// 数字で始まらない・助数詞がハングルにならない場合は数詞の候補なし。
+		assert_eq!(texts("han"), vec!["한"]);
+		assert_eq!(texts("3k"), vec!["3k"]);
+
+		// 数字は入力が空か数字のみの間だけ入力キー (それ以外は候補の選択用)。
+		let mut composer = Composer::new();
+		assert!(composer.is_input_key('1'));
+		composer.push('g');
+		assert!(!composer.is_input_key('1'));
+		assert!(!Composer::with_scheme(InputScheme::Dubeolsik).is_input_key('1'));
+	}
+
+	#[test]
+	fn test_consonant() {
+		let c = HangulConverter::new();
+		assert_eq!(c.consonant("m"), Some('ㅁ'));
+		assert_eq!(c.consonant("kk"), Some('ㄲ'));
+		assert_eq!(c.consonant("G"), Some('ㄱ'));
+		assert_eq!(c.consonant("ma"), None);
+		assert_eq!(c.consonant("mm"), None);
+		assert_eq!(c.consonant(""), None);
+
+		let consonant = |scheme: InputScheme, input: &str| {
+			let mut composer = Composer::with_scheme(scheme);
+			for c in input.chars() {
+				composer.push(c);
+			}
+			composer.consonant()
+		};
+		assert_eq!(consonant(InputScheme::Romaja, "d"), Some('ㄷ'));
+		assert_eq!(consonant(InputScheme::Romaja, "da"), None);
+		assert_eq!(consonant(InputScheme::Dubeolsik, "a"), Some('ㅁ'));
+		assert_eq!(consonant(InputScheme::Dubeolsik, "ak"), None);
+	}
+
+	#[test]
+	fn test_orphan_consonants_passthrough_by_default() {
+		let c = HangulConverter::new();
+		assert_eq!(c.convert("k"), "k");
+		assert_eq!(c.convert("k k k"), "kkk");
+		assert_eq!(c.convert("hangk"), "항k");
+	}
+
 	#[test]
 	fn test_complex_sentences() {
 		let c = HangulConverter::new();
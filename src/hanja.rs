@@ -0,0 +1,437 @@
+//! 漢字 (한자) 変換モジュール。
+//!
+//! ハングルの読みから漢字の候補を引く辞書を提供する。
+//! 辞書は libhangul の `hanja.txt` と同じ形式のテキストで、1行1項目の「読み:漢字:意味」。
+//!
+//! ```text
+//! # コメント
+//! 한:韓:나라 한
+//! 한국:韓國:
+//! ```
+//!
+//! `#` で始まる行と空行は無視する。意味は省略できる。
+//! 音節 (한 → 韓) と単語 (한국 → 韓國) を同じ辞書に並べ、入力の先頭から一致する
+//! 最長の読みを優先して候補にする。
+//!
+//! 逆に、漢字混じりの文をハングルの音にする ([`HanjaDict::to_hangul`])。
+//! 標準の音の表 (漢字1字の本来の音) と日本の新字体の対応表を同梱し、
+//! 語頭では頭音法則 (두음법칙) を適用する (李 → 이, 行李 → 행리)。
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::jamo::{self, Cho};
+
+/// 標準の漢字辞書。
+const STANDARD_HANJA: &str = include_str!("data/hanja.txt");
+
+/// 標準の漢字の音の表 (1行に「音:漢字の並び」)。
+const STANDARD_READINGS: &str = include_str!("data/hanja_readings.txt");
+
+/// 日本の新字体と旧字体の対応表 (1行に「新字体 旧字体」の2文字)。
+const STANDARD_VARIANTS: &str = include_str!("data/hanja_variants.txt");
+
+/// 初声 ㄴ のインデックス。
+const CHO_NIEUN: u32 = 2;
+/// 初声 ㄹ のインデックス。
+const CHO_RIEUL: u32 = 5;
+/// 初声 ㅇ のインデックス。
+const CHO_IEUNG: u32 = 11;
+/// 終声 ㄴ のインデックス。
+const JONG_NIEUN: u32 = 4;
+
+/// 漢字辞書の1項目。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HanjaEntry {
+    /// 漢字 (韓國)。
+    pub hanja: String,
+    /// 意味 (音節なら訓と音「나라 한」、単語なら語の説明)。空の場合もある。
+    pub meaning: String,
+}
+
+/// 漢字変換の候補。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HanjaCandidate {
+    /// 候補を選んだときの文字列 (読みを漢字に置き換え、残りはそのまま: 한국어 → 韓國어)。
+    pub text: String,
+    /// 置き換えたハングルの読み (한국)。
+    pub key: String,
+    /// 漢字 (韓國)。
+    pub hanja: String,
+    /// 意味。空の場合もある。
+    pub meaning: String,
+}
+
+impl HanjaCandidate {
+    /// 候補ウィンドウに表示する文字列 (漢字と意味: 「韓 나라 한」)。
+    pub fn label(&self) -> String {
+        if self.meaning.is_empty() {
+            self.hanja.clone()
+        } else {
+            format!("{} {}", self.hanja, self.meaning)
+        }
+    }
+}
+
+/// 漢字辞書 (読み → 漢字の項目、漢字 → 音)。
+pub struct HanjaDict {
+    /// 読み → 項目 (辞書の順)。
+    entries: HashMap<String, Vec<HanjaEntry>>,
+    /// 最長の読みの文字数。
+    max_key_len: usize,
+    /// 2字以上の漢字の語 → 読み (最初に追加した読み)。
+    words: HashMap<String, String>,
+    /// 最長の漢字の語の文字数。
+    max_word_len: usize,
+    /// 漢字1字 → 本来の音。
+    readings: HashMap<char, char>,
+    /// 新字体 → 旧字体。
+    variants: HashMap<char, char>,
+}
+
+impl HanjaDict {
+    /// 空の辞書を作成する (漢字の音の表も空)。
+    pub fn empty() -> Self {
+        Self {
+            entries: HashMap::new(),
+            max_key_len: 0,
+            words: HashMap::new(),
+            max_word_len: 0,
+            readings: HashMap::new(),
+            variants: HashMap::new(),
+        }
+    }
+
+    /// 標準の漢字辞書 (基本の音節と単語、漢字の音の表、新字体の対応表) を作成する。
+    pub fn standard() -> Self {
+        let mut dict = Self::empty();
+        dict.extend_str(STANDARD_HANJA);
+        dict.extend_readings(STANDARD_READINGS);
+        dict.extend_variants(STANDARD_VARIANTS);
+        dict
+    }
+
+    /// 項目を追加する。同じ読みと漢字の項目が既にあれば無視する。
+    ///
+    /// 追加した場合はtrueを返す。
+    pub fn add(&mut self, key: &str, hanja: &str, meaning: &str) -> bool {
+        if key.is_empty() || hanja.is_empty() {
+            return false;
+        }
+        let entries = self.entries.entry(key.to_string()).or_default();
+        if entries.iter().any(|e| e.hanja == hanja) {
+            return false;
+        }
+        entries.push(HanjaEntry { hanja: hanja.to_string(), meaning: meaning.to_string() });
+        self.max_key_len = self.max_key_len.max(key.chars().count());
+
+        let hanja_len = hanja.chars().count();
+        if hanja_len >= 2 && key.chars().count() == hanja_len {
+            self.words.entry(hanja.to_string()).or_insert_with(|| key.to_string());
+            self.max_word_len = self.max_word_len.max(hanja_len);
+        }
+        true
+    }
+
+    /// 漢字の音の表 (1行に「音:漢字の並び」、`#` で始まる行と空行は無視) を追加する。
+    ///
+    /// 音は頭音法則を適用しない本来の音 (李 → 리)。既に音のある漢字は無視する。
+    pub fn extend_readings(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((reading, chars)) = line.split_once(':') else {
+                continue;
+            };
+            let mut reading = reading.trim().chars();
+            let (Some(reading), None) = (reading.next(), reading.next()) else {
+                continue;
+            };
+            if !jamo::is_syllable(reading) {
+                continue;
+            }
+            for c in chars.trim().chars() {
+                self.readings.entry(c).or_insert(reading);
+            }
+        }
+    }
+
+    /// 字体の対応表 (1行に「異体字 韓国で使う字体」の2文字) を追加する。
+    pub fn extend_variants(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let chars: Vec<char> = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if let [variant, standard] = chars[..] {
+                self.variants.insert(variant, standard);
+            }
+        }
+    }
+
+    /// `hanja.txt` 形式のテキストの項目を追加する。
+    ///
+    /// 読みまたは漢字が空の行と、既にある項目は無視する。追加した項目数を返す。
+    pub fn extend_str(&mut self, text: &str) -> usize {
+        let mut count = 0;
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(3, ':');
+            let key = fields.next().unwrap_or_default().trim();
+            let hanja = fields.next().unwrap_or_default().trim();
+            let meaning = fields.next().unwrap_or_default().trim();
+            if self.add(key, hanja, meaning) {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// `hanja.txt` 形式のファイルの項目を追加する。
+    pub fn extend_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("漢字辞書を読み込めません: {}", path.display()))?;
+        self.extend_str(&content);
+        Ok(())
+    }
+
+    /// 読みに完全一致する項目を返す (辞書の順)。
+    pub fn lookup(&self, key: &str) -> &[HanjaEntry] {
+        self.entries.get(key).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    /// ハングル文字列の漢字候補を最大 `limit` 件返す。
+    ///
+    /// 文字列の先頭から一致する読みを長い順に引き、読みの部分を漢字に置き換えた候補を並べる
+    /// (한국어 → 韓國語, 韓國어, 韓어, 漢어 …)。先頭の空白は候補の文字列にそのまま残す。
+    pub fn candidates(&self, text: &str, limit: usize) -> Vec<HanjaCandidate> {
+        let trimmed = text.trim_start();
+        let leading = &text[..text.len() - trimmed.len()];
+        let ends: Vec<usize> = trimmed
+            .char_indices()
+            .map(|(i, c)| i + c.len_utf8())
+            .take(self.max_key_len)
+            .collect();
+
+        let mut candidates = Vec::new();
+        for &end in ends.iter().rev() {
+            let (key, rest) = trimmed.split_at(end);
+            for entry in self.lookup(key) {
+                if candidates.len() >= limit {
+                    return candidates;
+                }
+                candidates.push(HanjaCandidate {
+                    text: format!("{}{}{}", leading, entry.hanja, rest),
+                    key: key.to_string(),
+                    hanja: entry.hanja.clone(),
+                    meaning: entry.meaning.clone(),
+                });
+            }
+        }
+        candidates
+    }
+
+    /// 漢字1字の本来の音を返す (新字体も可: 国 → 국, 李 → 리)。
+    pub fn reading(&self, c: char) -> Option<char> {
+        self.readings.get(&self.standard_form(c)).copied()
+    }
+
+    /// 漢字 (新字体を含む) をハングルの音にする。音の分からない漢字とそれ以外の文字はそのまま出力する。
+    ///
+    /// 漢字の続き (語) ごとに、辞書の単語に一致する最長の語は辞書の読みを使い
+    /// (音樂 → 음악)、それ以外は1字ずつ音の表で読む。
+    /// 語頭の ㄹ・ㄴ には頭音法則を適用し (李 → 이, 來年 → 내년, 女子 → 여자)、
+    /// 母音と ㄴ の後の 렬・률 は 열・율 にする (羅列 → 나열, 比率 → 비율)。
+    pub fn to_hangul(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let standard: Vec<char> = chars.iter().map(|&c| self.standard_form(c)).collect();
+        let mut out = String::with_capacity(text.len());
+        let mut word_initial = true;
+        let mut i = 0;
+        while i < chars.len() {
+            if let Some((len, reading)) = self.match_word(&standard[i..]) {
+                out.push_str(reading);
+                word_initial = false;
+                i += len;
+                continue;
+            }
+            match self.readings.get(&standard[i]) {
+                Some(&reading) => {
+                    let reading = if word_initial {
+                        initial_sound(reading)
+                    } else {
+                        medial_sound(reading, out.chars().last())
+                    };
+                    out.push(reading);
+                    word_initial = false;
+                }
+                None => {
+                    out.push(chars[i]);
+                    word_initial = true;
+                }
+            }
+            i += 1;
+        }
+        out
+    }
+
+    /// 字体の対応表で韓国で使う字体にする (対応がなければそのまま)。
+    fn standard_form(&self, c: char) -> char {
+        self.variants.get(&c).copied().unwrap_or(c)
+    }
+
+    /// 先頭から一致する最長の漢字の語の (文字数, 読み) を返す。
+    fn match_word(&self, chars: &[char]) -> Option<(usize, &str)> {
+        (2..=self.max_word_len.min(chars.len())).rev().find_map(|len| {
+            let word: String = chars[..len].iter().collect();
+            self.words.get(&word).map(|reading| (len, reading.as_str()))
+        })
+    }
+}
+
+/// 語頭の音に頭音法則を適用する (리 → 이, 녀 → 여, 래 → 내)。
+///
+/// ㅑ・ㅕ・ㅖ・ㅛ・ㅠ・ㅣ の前の ㄹ・ㄴ は ㅇ、それ以外の母音の前の ㄹ は ㄴ になる。
+fn initial_sound(reading: char) -> char {
+    let Some((cho, jung, jong)) = jamo::decompose(reading) else {
+        return reading;
+    };
+    let y_vowel = matches!(jung.index(), 2 | 6 | 7 | 12 | 17 | 20);
+    let cho = match (cho.index(), y_vowel) {
+        (CHO_NIEUN | CHO_RIEUL, true) => CHO_IEUNG,
+        (CHO_RIEUL, false) => CHO_NIEUN,
+        _ => return reading,
+    };
+    Cho::new(cho).map_or(reading, |cho| jamo::compose(cho, jung, jong))
+}
+
+/// 語中の音を返す。母音と ㄴ の後の 렬・률 は 열・율 になる (羅列 → 나열, 比率 → 비율)。
+fn medial_sound(reading: char, prev: Option<char>) -> char {
+    let after_vowel_or_nieun = match prev.and_then(jamo::decompose) {
+        Some((_, _, jong)) => jong.is_none_or(|j| j.index() == JONG_NIEUN),
+        None => false,
+    };
+    match reading {
+        '렬' if after_vowel_or_nieun => '열',
+        '률' if after_vowel_or_nieun => '율',
+        _ => reading,
+    }
+}
+
+impl Default for HanjaDict {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_extend_str() {
+        let mut dict = HanjaDict::empty();
+        let text = "# comment\n\n한:韓:나라 한\n한:漢:한수 한\n한국:韓國:\n한:韓:重複\n:空:\n국\n";
+        assert_eq!(dict.extend_str(text), 3);
+
+        let entries = dict.lookup("한");
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0], HanjaEntry { hanja: "韓".to_string(), meaning: "나라 한".to_string() });
+        assert_eq!(entries[1].hanja, "漢");
+        assert_eq!(dict.lookup("한국")[0].meaning, "");
+        assert!(dict.lookup("국").is_empty());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let dict = HanjaDict::standard();
+
+        let candidates = dict.candidates("한국어", 20);
+        let texts: Vec<&str> = candidates.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(&texts[..4], ["韓國語", "韓國어", "韓국어", "漢국어"]);
+        assert_eq!(candidates[1].key, "한국");
+        assert_eq!(candidates[2].label(), "韓 나라 한");
+        assert_eq!(candidates[0].label(), "韓國語");
+
+        // 同音異義語は辞書の順。
+        let candidates = dict.candidates("의사", 3);
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].label(), "醫師 병을 고치는 사람");
+        assert_eq!(candidates[1].hanja, "意思");
+        assert_eq!(candidates[2].text, "意사");
+
+        assert_eq!(dict.candidates(" 학교", 1)[0].text, " 學校");
+        assert!(dict.candidates("없다", 5).is_empty());
+        assert!(dict.candidates("", 5).is_empty());
+        assert!(dict.candidates("한국", 0).is_empty());
+    }
+
+    #[test]
+    fn test_to_hangul() {
+        let dict = HanjaDict::standard();
+        let cases = [
+            ("大韓民國", "대한민국"),
+            ("韓国語", "한국어"),
+            ("国語の問題", "국어の문제"),
+            ("李", "이"),
+            ("行李", "행리"),
+            ("女子", "여자"),
+            ("男女", "남녀"),
+            ("來年", "내년"),
+            ("老人", "노인"),
+            ("歷史", "역사"),
+            ("経歴", "경력"),
+            ("羅列", "나열"),
+            ("比率", "비율"),
+            ("旋律", "선율"),
+            ("勝率", "승률"),
+            ("音楽", "음악"),
+            ("自動車", "자동차"),
+            ("李氏 朝鮮", "이씨 조선"),
+            ("漢字(한자)", "한자(한자)"),
+            ("ABC 123", "ABC 123"),
+            ("鑑", "鑑"),
+        ];
+        for (text, expected) in cases {
+            assert_eq!(dict.to_hangul(text), expected, "text: {}", text);
+        }
+        assert_eq!(dict.reading('国'), Some('국'));
+        assert_eq!(dict.reading('李'), Some('리'));
+        assert_eq!(HanjaDict::empty().to_hangul("韓國"), "韓國");
+    }
+
+    #[test]
+    fn test_standard_tables() {
+        // 音の表に重複がなく、新字体の対応先はすべて音を持つ。
+        let mut seen = std::collections::HashSet::new();
+        for line in STANDARD_READINGS.lines().filter(|l| !l.starts_with('#')) {
+            let (_, chars) = line.split_once(':').unwrap();
+            for c in chars.chars() {
+                assert!(seen.insert(c), "duplicate: {}", c);
+            }
+        }
+        let dict = HanjaDict::standard();
+        for (variant, standard) in &dict.variants {
+            assert!(dict.readings.contains_key(standard), "no reading: {} {}", variant, standard);
+        }
+    }
+
+    #[test]
+    fn test_extend_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("hanja.txt");
+        fs::write(&path, "참새:雀:작은 새\n").unwrap();
+
+        let mut dict = HanjaDict::standard();
+        dict.extend_file(&path).unwrap();
+        assert_eq!(dict.candidates("참새", 1)[0].text, "雀");
+        assert!(!dict.lookup("한국").is_empty());
+
+        assert!(dict.extend_file(&dir.path().join("missing.txt")).is_err());
+    }
+}
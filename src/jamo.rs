@@ -0,0 +1,586 @@
+//! 字母 (자모) の分解・合成モジュール。
+//!
+//! 完成形ハングル (U+AC00〜U+D7A3) と初声・中声・終声の相互変換、
+//! および字母の2つの表現の相互変換を提供する。
+//!
+//! - 結合型字母 (U+1100〜U+11FF): 初声・中声・終声で別のコードポイントを持つ
+//! - 互換字母 (U+3131〜U+318E): 初声と終声の区別がない (単独表示用)
+//!
+//! インデックスは Unicode の合成順序 (初声19・中声21・終声27) に従う。
+//!
+//! 古語の字母 (ㆍ, ㅿ, ㆁ, ㆆ など) は完成形にならないため、現代語の後に続く
+//! インデックス (初声19〜・中声21〜・終声28〜) を振り、結合型字母の列として出力する。
+
+/// 完成形ハングルの先頭コードポイント (가)。
+pub const SYLLABLE_BASE: u32 = 0xAC00;
+/// 完成形ハングルの末尾コードポイント (힣)。
+pub const SYLLABLE_LAST: u32 = 0xD7A3;
+
+/// 結合型初声の先頭 (ᄀ U+1100)。
+const CHOSEONG_BASE: u32 = 0x1100;
+/// 結合型中声の先頭 (ᅡ U+1161)。
+const JUNGSEONG_BASE: u32 = 0x1161;
+/// 結合型終声の先頭 (ᆨ U+11A8)。インデックス1に対応する。
+const JONGSEONG_BASE: u32 = 0x11A7;
+/// 互換字母の母音の先頭 (ㅏ U+314F)。中声と同じ順序。
+const COMPAT_VOWEL_BASE: u32 = 0x314F;
+
+/// 初声の数。
+const CHOSEONG_COUNT: u32 = 19;
+/// 中声の数。
+const JUNGSEONG_COUNT: u32 = 21;
+/// 終声の数 (終声なしを含む)。
+const JONGSEONG_COUNT: u32 = 28;
+
+/// 初声インデックス → 互換字母。
+const CHOSEONG_COMPAT: [char; 19] = [
+    'ㄱ', 'ㄲ', 'ㄴ', 'ㄷ', 'ㄸ', 'ㄹ', 'ㅁ', 'ㅂ', 'ㅃ', 'ㅅ',
+    'ㅆ', 'ㅇ', 'ㅈ', 'ㅉ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+/// 終声インデックス → 互換字母 (0 は終声なし)。
+const JONGSEONG_COMPAT: [char; 28] = [
+    ' ', 'ㄱ', 'ㄲ', 'ㄳ', 'ㄴ', 'ㄵ', 'ㄶ', 'ㄷ', 'ㄹ', 'ㄺ',
+    'ㄻ', 'ㄼ', 'ㄽ', 'ㄾ', 'ㄿ', 'ㅀ', 'ㅁ', 'ㅂ', 'ㅄ', 'ㅅ',
+    'ㅆ', 'ㅇ', 'ㅈ', 'ㅊ', 'ㅋ', 'ㅌ', 'ㅍ', 'ㅎ',
+];
+
+/// 初声インデックス → 終声インデックス (終声にならない子音は0)。
+const CHOSEONG_TO_JONGSEONG: [u32; 19] = [
+    1, 2, 4, 7, 0, 8, 16, 17, 0, 19,
+    20, 21, 22, 0, 23, 24, 25, 26, 27,
+];
+
+/// 二重終声の分解 (二重終声, 残る終声, 分かれる子音の初声インデックス)。
+const COMPOUND_JONGSEONG: [(u32, u32, u32); 11] = [
+    (3, 1, 9),    // ㄳ = ㄱ + ㅅ
+    (5, 4, 12),   // ㄵ = ㄴ + ㅈ
+    (6, 4, 18),   // ㄶ = ㄴ + ㅎ
+    (9, 8, 0),    // ㄺ = ㄹ + ㄱ
+    (10, 8, 6),   // ㄻ = ㄹ + ㅁ
+    (11, 8, 7),   // ㄼ = ㄹ + ㅂ
+    (12, 8, 9),   // ㄽ = ㄹ + ㅅ
+    (13, 8, 16),  // ㄾ = ㄹ + ㅌ
+    (14, 8, 17),  // ㄿ = ㄹ + ㅍ
+    (15, 8, 18),  // ㅀ = ㄹ + ㅎ
+    (18, 17, 9),  // ㅄ = ㅂ + ㅅ
+];
+
+/// 古語の初声 (結合型字母, 互換字母)。インデックスは 19 から。
+const ARCHAIC_CHOSEONG: [(char, Option<char>); 19] = [
+    ('\u{1140}', Some('ㅿ')), // PANSIOS
+    ('\u{114C}', Some('ㆁ')), // YESIEUNG
+    ('\u{1159}', Some('ㆆ')), // YEORINHIEUH
+    ('\u{112B}', Some('ㅸ')), // KAPYEOUNPIEUP
+    ('\u{1157}', Some('ㆄ')), // KAPYEOUNPHIEUPH
+    ('\u{1158}', Some('ㆅ')), // SSANGHIEUH
+    ('\u{111E}', Some('ㅲ')), // PIEUP-KIYEOK
+    ('\u{1120}', Some('ㅳ')), // PIEUP-TIKEUT
+    ('\u{1121}', Some('ㅄ')), // PIEUP-SIOS
+    ('\u{1122}', Some('ㅴ')), // PIEUP-SIOS-KIYEOK
+    ('\u{1123}', Some('ㅵ')), // PIEUP-SIOS-TIKEUT
+    ('\u{1127}', Some('ㅶ')), // PIEUP-CIEUC
+    ('\u{1129}', Some('ㅷ')), // PIEUP-THIEUTH
+    ('\u{112D}', Some('ㅺ')), // SIOS-KIYEOK
+    ('\u{112E}', Some('ㅻ')), // SIOS-NIEUN
+    ('\u{112F}', Some('ㅼ')), // SIOS-TIKEUT
+    ('\u{1132}', Some('ㅽ')), // SIOS-PIEUP
+    ('\u{1136}', Some('ㅾ')), // SIOS-CIEUC
+    ('\u{A966}', None),      // RIEUL-TIKEUT
+];
+
+/// 古語の中声 (結合型字母, 互換字母)。インデックスは 21 から。
+const ARCHAIC_JUNGSEONG: [(char, Option<char>); 10] = [
+    ('\u{119E}', Some('ㆍ')), // ARAEA
+    ('\u{11A1}', Some('ㆎ')), // ARAEA-I
+    ('\u{119F}', None),      // ARAEA-EO
+    ('\u{1184}', Some('ㆇ')), // YO-YA
+    ('\u{1185}', Some('ㆈ')), // YO-YAE
+    ('\u{1188}', Some('ㆉ')), // YO-I
+    ('\u{1191}', Some('ㆊ')), // YU-YEO
+    ('\u{1192}', Some('ㆋ')), // YU-YE
+    ('\u{1194}', Some('ㆌ')), // YU-I
+    ('\u{D7B0}', None),      // O-YEO
+];
+
+/// 古語の終声 (結合型字母, 互換字母)。インデックスは 28 から。
+const ARCHAIC_JONGSEONG: [(char, Option<char>); 11] = [
+    ('\u{11EB}', Some('ㅿ')), // PANSIOS
+    ('\u{11F0}', Some('ㆁ')), // YESIEUNG
+    ('\u{11F9}', Some('ㆆ')), // YEORINHIEUH
+    ('\u{11E6}', Some('ㅸ')), // KAPYEOUNPIEUP
+    ('\u{11F4}', Some('ㆄ')), // KAPYEOUNPHIEUPH
+    ('\u{11CC}', Some('ㅩ')), // RIEUL-KIYEOK-SIOS
+    ('\u{11D7}', Some('ㅬ')), // RIEUL-PANSIOS
+    ('\u{11D9}', Some('ㅭ')), // RIEUL-YEORINHIEUH
+    ('\u{11DD}', Some('ㅯ')), // MIEUM-SIOS
+    ('\u{11F1}', Some('ㆂ')), // YESIEUNG-SIOS
+    ('\u{D7E3}', Some('ㅳ')), // PIEUP-TIKEUT
+];
+
+/// 初声 (ㄱ〜ㅎ の19字母)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Cho(u32);
+
+/// 中声 (ㅏ〜ㅣ の21字母)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Jung(u32);
+
+/// 終声 (ㄱ〜ㅎ の27字母、二重終声を含む)。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Jong(u32);
+
+impl Cho {
+    /// インデックス (0〜18) から初声を作成する。
+    pub fn new(index: u32) -> Option<Self> {
+        (index < CHOSEONG_COUNT).then_some(Self(index))
+    }
+
+    /// インデックス (0 = ㄱ 〜 18 = ㅎ) を返す。
+    pub fn index(self) -> u32 {
+        self.0
+    }
+
+    /// 結合型字母 (U+1100〜U+1112) に変換する。
+    pub fn to_conjoining(self) -> char {
+        char::from_u32(CHOSEONG_BASE + self.0).unwrap_or('?')
+    }
+
+    /// 互換字母に変換する。
+    pub fn to_compat(self) -> char {
+        CHOSEONG_COMPAT[self.0 as usize]
+    }
+
+    /// 結合型字母の初声から作成する。
+    pub fn from_conjoining(c: char) -> Option<Self> {
+        (c as u32).checked_sub(CHOSEONG_BASE).and_then(Self::new)
+    }
+
+    /// 互換字母から作成する。初声にならない字母 (ㄳ など) はNone。
+    pub fn from_compat(c: char) -> Option<Self> {
+        CHOSEONG_COMPAT.iter().position(|&j| j == c).map(|i| Self(i as u32))
+    }
+
+    /// 同じ子音の終声に変換する。終声にならない子音 (ㄸ, ㅃ, ㅉ) はNone。
+    pub fn to_jong(self) -> Option<Jong> {
+        Jong::new(CHOSEONG_TO_JONGSEONG[self.0 as usize])
+    }
+}
+
+impl Jung {
+    /// インデックス (0〜20) から中声を作成する。
+    pub fn new(index: u32) -> Option<Self> {
+        (index < JUNGSEONG_COUNT).then_some(Self(index))
+    }
+
+    /// インデックス (0 = ㅏ 〜 20 = ㅣ) を返す。
+    pub fn index(self) -> u32 {
+        self.0
+    }
+
+    /// 結合型字母 (U+1161〜U+1175) に変換する。
+    pub fn to_conjoining(self) -> char {
+        char::from_u32(JUNGSEONG_BASE + self.0).unwrap_or('?')
+    }
+
+    /// 互換字母 (U+314F〜U+3163) に変換する。
+    pub fn to_compat(self) -> char {
+        char::from_u32(COMPAT_VOWEL_BASE + self.0).unwrap_or('?')
+    }
+
+    /// 結合型字母の中声から作成する。
+    pub fn from_conjoining(c: char) -> Option<Self> {
+        (c as u32).checked_sub(JUNGSEONG_BASE).and_then(Self::new)
+    }
+
+    /// 互換字母から作成する。
+    pub fn from_compat(c: char) -> Option<Self> {
+        (c as u32).checked_sub(COMPAT_VOWEL_BASE).and_then(Self::new)
+    }
+}
+
+impl Jong {
+    /// インデックス (1〜27) から終声を作成する。0 (終声なし) はNone。
+    pub fn new(index: u32) -> Option<Self> {
+        (1..JONGSEONG_COUNT).contains(&index).then_some(Self(index))
+    }
+
+    /// インデックス (1 = ㄱ 〜 27 = ㅎ) を返す。
+    pub fn index(self) -> u32 {
+        self.0
+    }
+
+    /// 結合型字母 (U+11A8〜U+11C2) に変換する。
+    pub fn to_conjoining(self) -> char {
+        char::from_u32(JONGSEONG_BASE + self.0).unwrap_or('?')
+    }
+
+    /// 互換字母に変換する。
+    pub fn to_compat(self) -> char {
+        JONGSEONG_COMPAT[self.0 as usize]
+    }
+
+    /// 結合型字母の終声から作成する。
+    pub fn from_conjoining(c: char) -> Option<Self> {
+        (c as u32).checked_sub(JONGSEONG_BASE).and_then(Self::new)
+    }
+
+    /// 互換字母から作成する。終声にならない字母 (ㄸ など) はNone。
+    pub fn from_compat(c: char) -> Option<Self> {
+        JONGSEONG_COMPAT[1..]
+            .iter()
+            .position(|&j| j == c)
+            .map(|i| Self(i as u32 + 1))
+    }
+
+    /// 同じ子音の初声に変換する。二重終声はNone。
+    pub fn to_cho(self) -> Option<Cho> {
+        CHOSEONG_TO_JONGSEONG
+            .iter()
+            .position(|&j| j == self.0)
+            .map(|i| Cho(i as u32))
+    }
+
+    /// 二重終声を (残る終声, 分かれる子音の初声) に分解する。
+    ///
+    /// 二重終声でなければNone。濃音 (ㄲ, ㅆ) は1つの子音として扱う。
+    pub fn split(self) -> Option<(Jong, Cho)> {
+        COMPOUND_JONGSEONG
+            .iter()
+            .find(|&&(compound, _, _)| compound == self.0)
+            .map(|&(_, first, second)| (Jong(first), Cho(second)))
+    }
+
+    /// 終声に初声の子音を加えて二重終声を作る。作れなければNone。
+    pub fn combine(self, cho: Cho) -> Option<Jong> {
+        COMPOUND_JONGSEONG
+            .iter()
+            .find(|&&(_, first, second)| first == self.0 && second == cho.0)
+            .map(|&(compound, _, _)| Jong(compound))
+    }
+}
+
+/// 初声・中声・終声のインデックスから完成形ハングルを合成する。
+///
+/// 範囲外のインデックスは '?' になる。
+pub(crate) fn compose_index(cho: u32, jung: u32, jong: u32) -> char {
+    let code = SYLLABLE_BASE + (cho * JUNGSEONG_COUNT + jung) * JONGSEONG_COUNT + jong;
+    char::from_u32(code).unwrap_or('?')
+}
+
+/// 初声・中声・終声から完成形ハングルを合成する。
+pub fn compose(cho: Cho, jung: Jung, jong: Option<Jong>) -> char {
+    compose_index(cho.0, jung.0, jong.map_or(0, |j| j.0))
+}
+
+/// 完成形ハングルを初声・中声・終声に分解する。
+///
+/// 完成形ハングル以外はNoneを返す。
+pub fn decompose(c: char) -> Option<(Cho, Jung, Option<Jong>)> {
+    let offset = (c as u32).checked_sub(SYLLABLE_BASE)?;
+    if c as u32 > SYLLABLE_LAST {
+        return None;
+    }
+    Some((
+        Cho(offset / (JUNGSEONG_COUNT * JONGSEONG_COUNT)),
+        Jung((offset % (JUNGSEONG_COUNT * JONGSEONG_COUNT)) / JONGSEONG_COUNT),
+        Jong::new(offset % JONGSEONG_COUNT),
+    ))
+}
+
+/// 古語を含む初声インデックスの結合型字母を返す。
+pub(crate) fn choseong_char(index: u32) -> Option<char> {
+    match Cho::new(index) {
+        Some(cho) => Some(cho.to_conjoining()),
+        None => archaic(&ARCHAIC_CHOSEONG, index, CHOSEONG_COUNT).map(|(c, _)| c),
+    }
+}
+
+/// 古語を含む中声インデックスの結合型字母を返す。
+pub(crate) fn jungseong_char(index: u32) -> Option<char> {
+    match Jung::new(index) {
+        Some(jung) => Some(jung.to_conjoining()),
+        None => archaic(&ARCHAIC_JUNGSEONG, index, JUNGSEONG_COUNT).map(|(c, _)| c),
+    }
+}
+
+/// 古語を含む終声インデックスの結合型字母を返す。0 (終声なし) はNone。
+pub(crate) fn jongseong_char(index: u32) -> Option<char> {
+    match Jong::new(index) {
+        Some(jong) => Some(jong.to_conjoining()),
+        None => archaic(&ARCHAIC_JONGSEONG, index, JONGSEONG_COUNT).map(|(c, _)| c),
+    }
+}
+
+/// 古語を含む初声インデックスの互換字母を返す。互換字母がない字母はNone。
+pub(crate) fn choseong_compat(index: u32) -> Option<char> {
+    match Cho::new(index) {
+        Some(cho) => Some(cho.to_compat()),
+        None => archaic(&ARCHAIC_CHOSEONG, index, CHOSEONG_COUNT).and_then(|(_, c)| c),
+    }
+}
+
+/// 古語を含む中声インデックスの互換字母を返す。互換字母がない字母はNone。
+pub(crate) fn jungseong_compat(index: u32) -> Option<char> {
+    match Jung::new(index) {
+        Some(jung) => Some(jung.to_compat()),
+        None => archaic(&ARCHAIC_JUNGSEONG, index, JUNGSEONG_COUNT).and_then(|(_, c)| c),
+    }
+}
+
+/// 古語を含む終声インデックスの互換字母を返す。互換字母がない字母はNone。
+pub(crate) fn jongseong_compat(index: u32) -> Option<char> {
+    match Jong::new(index) {
+        Some(jong) => Some(jong.to_compat()),
+        None => archaic(&ARCHAIC_JONGSEONG, index, JONGSEONG_COUNT).and_then(|(_, c)| c),
+    }
+}
+
+/// 結合型字母 (古語を含む) の初声インデックスを返す。
+pub(crate) fn choseong_index(c: char) -> Option<u32> {
+    Cho::from_conjoining(c)
+        .map(Cho::index)
+        .or_else(|| archaic_index(&ARCHAIC_CHOSEONG, c, CHOSEONG_COUNT))
+}
+
+/// 結合型字母 (古語を含む) の中声インデックスを返す。
+pub(crate) fn jungseong_index(c: char) -> Option<u32> {
+    Jung::from_conjoining(c)
+        .map(Jung::index)
+        .or_else(|| archaic_index(&ARCHAIC_JUNGSEONG, c, JUNGSEONG_COUNT))
+}
+
+/// 結合型字母 (古語を含む) の終声インデックスを返す。
+pub(crate) fn jongseong_index(c: char) -> Option<u32> {
+    Jong::from_conjoining(c)
+        .map(Jong::index)
+        .or_else(|| archaic_index(&ARCHAIC_JONGSEONG, c, JONGSEONG_COUNT))
+}
+
+/// 互換字母 (古語を含む) の初声インデックスを返す。初声にならない字母 (ㄳ など) はNone。
+pub(crate) fn choseong_index_from_compat(c: char) -> Option<u32> {
+    Cho::from_compat(c)
+        .map(Cho::index)
+        .or_else(|| archaic_compat_index(&ARCHAIC_CHOSEONG, c, CHOSEONG_COUNT))
+}
+
+/// 互換字母 (古語を含む) の中声インデックスを返す。
+pub(crate) fn jungseong_index_from_compat(c: char) -> Option<u32> {
+    Jung::from_compat(c)
+        .map(Jung::index)
+        .or_else(|| archaic_compat_index(&ARCHAIC_JUNGSEONG, c, JUNGSEONG_COUNT))
+}
+
+/// 互換字母 (古語を含む) の終声インデックスを返す。終声にならない字母 (ㄸ など) はNone。
+pub(crate) fn jongseong_index_from_compat(c: char) -> Option<u32> {
+    Jong::from_compat(c)
+        .map(Jong::index)
+        .or_else(|| archaic_compat_index(&ARCHAIC_JONGSEONG, c, JONGSEONG_COUNT))
+}
+
+/// 古語を含むインデックスから音節を `out` に追加する。
+///
+/// 現代語の字母のみなら完成形ハングル、古語を含む場合は結合型字母の列
+/// (初声・中声・終声) を追加する。
+pub(crate) fn push_syllable(cho: u32, jung: u32, jong: u32, out: &mut String) {
+    if cho < CHOSEONG_COUNT && jung < JUNGSEONG_COUNT && jong < JONGSEONG_COUNT {
+        out.push(compose_index(cho, jung, jong));
+        return;
+    }
+    out.push(choseong_char(cho).unwrap_or('?'));
+    out.push(jungseong_char(jung).unwrap_or('?'));
+    if jong != 0 {
+        out.push(jongseong_char(jong).unwrap_or('?'));
+    }
+}
+
+/// 古語の字母表から現代語の数に続くインデックスの字母を取得する。
+fn archaic(table: &[(char, Option<char>)], index: u32, base: u32) -> Option<(char, Option<char>)> {
+    index.checked_sub(base).and_then(|i| table.get(i as usize)).copied()
+}
+
+/// 古語の字母表で結合型字母のインデックス (現代語の数に続く) を検索する。
+fn archaic_index(table: &[(char, Option<char>)], c: char, base: u32) -> Option<u32> {
+    table.iter().position(|&(j, _)| j == c).map(|i| base + i as u32)
+}
+
+/// 古語の字母表で互換字母のインデックス (現代語の数に続く) を検索する。
+fn archaic_compat_index(table: &[(char, Option<char>)], c: char, base: u32) -> Option<u32> {
+    table.iter().position(|&(_, compat)| compat == Some(c)).map(|i| base + i as u32)
+}
+
+/// 完成形ハングルか判定する。
+pub fn is_syllable(c: char) -> bool {
+    (SYLLABLE_BASE..=SYLLABLE_LAST).contains(&(c as u32))
+}
+
+/// 終声 (パッチム) を持つ完成形ハングルか判定する。
+pub fn has_final(c: char) -> bool {
+    matches!(decompose(c), Some((_, _, Some(_))))
+}
+
+/// 結合型字母を互換字母に変換する。結合型字母 (現代語) 以外はNone。
+pub fn conjoining_to_compat(c: char) -> Option<char> {
+    Cho::from_conjoining(c)
+        .map(Cho::to_compat)
+        .or_else(|| Jung::from_conjoining(c).map(Jung::to_compat))
+        .or_else(|| Jong::from_conjoining(c).map(Jong::to_compat))
+}
+
+/// 互換字母を結合型字母に変換する。
+///
+/// 子音は初声として扱い、初声にならない字母 (ㄳ など) のみ終声になる。
+/// 互換字母 (現代語) 以外はNone。
+pub fn compat_to_conjoining(c: char) -> Option<char> {
+    Cho::from_compat(c)
+        .map(Cho::to_conjoining)
+        .or_else(|| Jung::from_compat(c).map(Jung::to_conjoining))
+        .or_else(|| Jong::from_compat(c).map(Jong::to_conjoining))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decompose_compose_roundtrip() {
+        for code in SYLLABLE_BASE..=SYLLABLE_LAST {
+            let c = char::from_u32(code).unwrap();
+            let (cho, jung, jong) = decompose(c).unwrap();
+            assert_eq!(compose(cho, jung, jong), c);
+        }
+    }
+
+    #[test]
+    fn test_decompose() {
+        let (cho, jung, jong) = decompose('한').unwrap();
+        assert_eq!(cho.to_compat(), 'ㅎ');
+        assert_eq!(jung.to_compat(), 'ㅏ');
+        assert_eq!(jong.map(Jong::to_compat), Some('ㄴ'));
+
+        let (cho, jung, jong) = decompose('가').unwrap();
+        assert_eq!((cho.index(), jung.index(), jong), (0, 0, None));
+
+        let (_, _, jong) = decompose('닭').unwrap();
+        assert_eq!(jong.map(Jong::to_compat), Some('ㄺ'));
+
+        assert_eq!(decompose('a'), None);
+        assert_eq!(decompose('ㄱ'), None);
+        assert_eq!(decompose('\u{D7A4}'), None);
+    }
+
+    #[test]
+    fn test_has_final() {
+        assert!(has_final('한'));
+        assert!(has_final('닭'));
+        assert!(!has_final('가'));
+        assert!(!has_final('a'));
+        assert!(is_syllable('힣'));
+        assert!(!is_syllable('ㅎ'));
+    }
+
+    #[test]
+    fn test_new_range() {
+        assert!(Cho::new(18).is_some());
+        assert!(Cho::new(19).is_none());
+        assert!(Jung::new(20).is_some());
+        assert!(Jung::new(21).is_none());
+        assert!(Jong::new(0).is_none());
+        assert!(Jong::new(27).is_some());
+        assert!(Jong::new(28).is_none());
+    }
+
+    #[test]
+    fn test_conjoining_compat() {
+        assert_eq!(Cho::new(0).unwrap().to_conjoining(), '\u{1100}');
+        assert_eq!(Jung::new(0).unwrap().to_conjoining(), '\u{1161}');
+        assert_eq!(Jong::new(1).unwrap().to_conjoining(), '\u{11A8}');
+        assert_eq!(Jong::new(27).unwrap().to_conjoining(), '\u{11C2}');
+
+        assert_eq!(conjoining_to_compat('\u{1100}'), Some('ㄱ'));
+        assert_eq!(conjoining_to_compat('\u{11A8}'), Some('ㄱ'));
+        assert_eq!(conjoining_to_compat('\u{1175}'), Some('ㅣ'));
+        assert_eq!(conjoining_to_compat('\u{11AA}'), Some('ㄳ'));
+        assert_eq!(conjoining_to_compat('가'), None);
+
+        assert_eq!(compat_to_conjoining('ㄱ'), Some('\u{1100}'));
+        assert_eq!(compat_to_conjoining('ㅏ'), Some('\u{1161}'));
+        assert_eq!(compat_to_conjoining('ㄳ'), Some('\u{11AA}'));
+        assert_eq!(compat_to_conjoining('a'), None);
+
+        // すべての字母が往復する。
+        for i in 0..19 {
+            let cho = Cho::new(i).unwrap();
+            assert_eq!(Cho::from_conjoining(cho.to_conjoining()), Some(cho));
+            assert_eq!(Cho::from_compat(cho.to_compat()), Some(cho));
+        }
+        for i in 0..21 {
+            let jung = Jung::new(i).unwrap();
+            assert_eq!(Jung::from_conjoining(jung.to_conjoining()), Some(jung));
+            assert_eq!(Jung::from_compat(jung.to_compat()), Some(jung));
+        }
+        for i in 1..28 {
+            let jong = Jong::new(i).unwrap();
+            assert_eq!(Jong::from_conjoining(jong.to_conjoining()), Some(jong));
+            assert_eq!(Jong::from_compat(jong.to_compat()), Some(jong));
+        }
+    }
+
+    #[test]
+    fn test_cho_jong_conversion() {
+        let giyeok = Cho::from_compat('ㄱ').unwrap();
+        assert_eq!(giyeok.to_jong(), Jong::from_compat('ㄱ'));
+        assert_eq!(Cho::from_compat('ㄸ').unwrap().to_jong(), None);
+        assert_eq!(Jong::from_compat('ㅎ').unwrap().to_cho(), Cho::from_compat('ㅎ'));
+        assert_eq!(Jong::from_compat('ㄺ').unwrap().to_cho(), None);
+    }
+
+    #[test]
+    fn test_compound_jongseong() {
+        let rieul_giyeok = Jong::from_compat('ㄺ').unwrap();
+        let (first, second) = rieul_giyeok.split().unwrap();
+        assert_eq!(first.to_compat(), 'ㄹ');
+        assert_eq!(second.to_compat(), 'ㄱ');
+        assert_eq!(first.combine(second), Some(rieul_giyeok));
+
+        assert_eq!(Jong::from_compat('ㄲ').unwrap().split(), None);
+        let rieul = Jong::from_compat('ㄹ').unwrap();
+        assert_eq!(rieul.combine(Cho::from_compat('ㄴ').unwrap()), None);
+    }
+
+    #[test]
+    fn test_archaic_jamo() {
+        assert_eq!(choseong_index('\u{1140}'), Some(19));
+        assert_eq!(choseong_char(19), Some('\u{1140}'));
+        assert_eq!(choseong_compat(19), Some('ㅿ'));
+        assert_eq!(jungseong_index('\u{119E}'), Some(21));
+        assert_eq!(jongseong_index('\u{11F0}'), Some(29));
+        assert_eq!(jongseong_compat(29), Some('ㆁ'));
+        assert_eq!(choseong_index('\u{A966}').and_then(choseong_compat), None);
+        assert_eq!(choseong_char(100), None);
+        for (c, _) in ARCHAIC_CHOSEONG {
+            assert_eq!(choseong_index(c).and_then(choseong_char), Some(c));
+        }
+        for (c, _) in ARCHAIC_JUNGSEONG {
+            assert_eq!(jungseong_index(c).and_then(jungseong_char), Some(c));
+        }
+        for (c, _) in ARCHAIC_JONGSEONG {
+            assert_eq!(jongseong_index(c).and_then(jongseong_char), Some(c));
+        }
+    }
+
+    #[test]
+    fn test_push_syllable() {
+        let mut out = String::new();
+        push_syllable(18, 0, 4, &mut out);
+        assert_eq!(out, "한");
+
+        // 古語を含む場合は現代語の字母も結合型字母になる。
+        out.clear();
+        push_syllable(18, 21, 4, &mut out);
+        assert_eq!(out, "\u{1112}\u{119E}\u{11AB}");
+        out.clear();
+        push_syllable(19, 0, 0, &mut out);
+        assert_eq!(out, "\u{1140}\u{1161}");
+    }
+}
@@ -0,0 +1,271 @@
+//! 助詞 (조사) の選択モジュール。
+//!
+//! 直前の語の最後の音節に終声 (パッチム) があるかで形が変わる助詞を選ぶ。
+//!
+//! - 은/는, 이/가, 을/를, 과/와: 終声があれば前、なければ後の形
+//! - 으로/로: 終声があれば 으로。ただし終声 ㄹ の後は 로 (서울로)
+//!
+//! 最後の文字は完成形の音節のほか、字母 (ㅋ, 結合型字母の終声)、数字 (漢数詞の読み:
+//! 3 → 삼 → 은) で判定する。英字など読みの分からない文字の後は両方の形 (은(는), (으)로) を残す。
+//!
+//! テンプレート (`{name}은/는 {seoul}에 살아요`) の展開では、`{キー}` を値に置き換えたうえで、
+//! 助詞の表記 (은/는, 는/은, 은(는), (으)로 など) を直前の文字に合う形にする。
+
+use crate::jamo::{self, Cho, Jong, Jung};
+use crate::numeral;
+
+/// 終声 ㄹ のインデックス。
+const JONG_RIEUL: u32 = 8;
+
+/// 終声で形が変わる助詞。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Josa {
+    /// 主題の 은/는。
+    EunNeun,
+    /// 主格の 이/가。
+    IGa,
+    /// 目的格の 을/를。
+    EulReul,
+    /// 共格の 과/와。
+    GwaWa,
+    /// 道具・方向の 으로/로。
+    EuroRo,
+}
+
+impl Josa {
+    /// すべての助詞。
+    pub const ALL: [Josa; 5] = [Josa::EunNeun, Josa::IGa, Josa::EulReul, Josa::GwaWa, Josa::EuroRo];
+
+    /// (終声の後の形, 母音の後の形) を返す。
+    pub fn forms(self) -> (&'static str, &'static str) {
+        match self {
+            Josa::EunNeun => ("은", "는"),
+            Josa::IGa => ("이", "가"),
+            Josa::EulReul => ("을", "를"),
+            Josa::GwaWa => ("과", "와"),
+            Josa::EuroRo => ("으로", "로"),
+        }
+    }
+
+    /// 直前の文字の終声に合う形を返す。読みの分からない文字の後は両方の形 (은(는), (으)로)。
+    pub fn select(self, ending: Ending) -> String {
+        let (consonant, vowel) = self.forms();
+        match ending {
+            Ending::Vowel => vowel.to_string(),
+            Ending::Rieul if self == Josa::EuroRo => vowel.to_string(),
+            Ending::Rieul | Ending::Consonant => consonant.to_string(),
+            Ending::Unknown => self.both(),
+        }
+    }
+
+    /// 両方の形を併記した表記 (은(는), (으)로) を返す。
+    pub fn both(self) -> String {
+        match self {
+            Josa::EuroRo => "(으)로".to_string(),
+            _ => {
+                let (consonant, vowel) = self.forms();
+                format!("{}({})", consonant, vowel)
+            }
+        }
+    }
+
+    /// 助詞の表記 (은/는, 는/은, 은(는), 는(은), (으)로 など) を返す。
+    pub fn notations(self) -> Vec<String> {
+        let (a, b) = self.forms();
+        let mut notations = vec![format!("{}/{}", a, b), format!("{}/{}", b, a), format!("{}({})", a, b), format!("{}({})", b, a)];
+        if self == Josa::EuroRo {
+            notations.push(self.both());
+        }
+        notations
+    }
+
+    /// 助詞の表記から助詞を返す。
+    pub fn parse(notation: &str) -> Option<Josa> {
+        Josa::ALL.into_iter().find(|josa| josa.notations().iter().any(|n| n == notation))
+    }
+}
+
+/// 語の終わりの音。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Ending {
+    /// 母音 (終声なし)。
+    Vowel,
+    /// 終声 ㄹ。
+    Rieul,
+    /// ㄹ 以外の終声。
+    Consonant,
+    /// 読みが分からない (英字・記号など)。
+    Unknown,
+}
+
+/// 語の終わりの音を返す。
+///
+/// 末尾の記号・スペース (』, ", ) など) は飛ばし、最後の文字 (数字は漢数詞の読み) で判定する。
+pub fn ending(word: &str) -> Ending {
+    let word = word.trim_end_matches(|c: char| !c.is_alphanumeric());
+    let digits = word.len() - word.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        let reading = numeral::parse(&word[word.len() - digits..]).map(numeral::sino);
+        return reading.as_deref().map_or(Ending::Unknown, ending);
+    }
+    let Some(c) = word.chars().last() else {
+        return Ending::Unknown;
+    };
+    if let Some((_, _, jong)) = jamo::decompose(c) {
+        return jong.map_or(Ending::Vowel, jong_ending);
+    }
+    if let Some(jong) = Jong::from_conjoining(c) {
+        jong_ending(jong)
+    } else if Jung::from_conjoining(c).is_some() || Jung::from_compat(c).is_some() {
+        Ending::Vowel
+    } else if c == 'ㄹ' {
+        Ending::Rieul
+    } else if Cho::from_compat(c).is_some() || Jong::from_compat(c).is_some() {
+        Ending::Consonant
+    } else {
+        Ending::Unknown
+    }
+}
+
+/// 終声の音を返す。
+fn jong_ending(jong: Jong) -> Ending {
+    if jong.index() == JONG_RIEUL {
+        Ending::Rieul
+    } else {
+        Ending::Consonant
+    }
+}
+
+/// 語に助詞を付ける (사과 + 을/를 → 사과를, 서울 + 으로/로 → 서울로)。
+pub fn attach(word: &str, josa: Josa) -> String {
+    format!("{}{}", word, josa.select(ending(word)))
+}
+
+/// 文字列中の助詞の表記を、直前の文字に合う形にする (사과을/를 → 사과를)。
+///
+/// 文字列の先頭の表記はそのまま残す。
+pub fn resolve(text: &str) -> String {
+    let mut notations: Vec<(String, Josa)> = Josa::ALL
+        .into_iter()
+        .flat_map(|josa| josa.notations().into_iter().map(move |n| (n, josa)))
+        .collect();
+    // 長い表記から照合する (으로/로 を 로/... より先に)。
+    notations.sort_by_key(|(n, _)| std::cmp::Reverse(n.len()));
+
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let found = notations.iter().find(|(n, _)| rest.starts_with(n.as_str()));
+        match found {
+            Some((notation, josa)) if !result.is_empty() => {
+                result.push_str(&josa.select(ending(&result)));
+                rest = &rest[notation.len()..];
+            }
+            _ => {
+                result.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    result
+}
+
+/// テンプレートの `{キー}` を値に置き換え、助詞の表記を直前の文字に合う形にする。
+///
+/// `value` はキーの値を返す。閉じていない `{` と空の `{}` はそのまま残す。
+pub fn expand(template: &str, value: impl Fn(&str) -> String) -> String {
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        text.push_str(&rest[..start]);
+        match rest[start + 1..].find('}') {
+            Some(len) if len > 0 => {
+                text.push_str(&value(&rest[start + 1..start + 1 + len]));
+                rest = &rest[start + len + 2..];
+            }
+            _ => {
+                text.push('{');
+                rest = &rest[start + 1..];
+            }
+        }
+    }
+    text.push_str(rest);
+    resolve(&text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach() {
+        assert_eq!(attach("책", Josa::EunNeun), "책은");
+        assert_eq!(attach("사과", Josa::EunNeun), "사과는");
+        assert_eq!(attach("학생", Josa::IGa), "학생이");
+        assert_eq!(attach("친구", Josa::IGa), "친구가");
+        assert_eq!(attach("밥", Josa::EulReul), "밥을");
+        assert_eq!(attach("커피", Josa::EulReul), "커피를");
+        assert_eq!(attach("선생님", Josa::GwaWa), "선생님과");
+        assert_eq!(attach("나", Josa::GwaWa), "나와");
+    }
+
+    #[test]
+    fn test_euro_ro() {
+        assert_eq!(attach("집", Josa::EuroRo), "집으로");
+        assert_eq!(attach("학교", Josa::EuroRo), "학교로");
+        // 終声 ㄹ の後は 로。
+        assert_eq!(attach("서울", Josa::EuroRo), "서울로");
+        assert_eq!(attach("연필", Josa::EuroRo), "연필로");
+        assert_eq!(attach("서울", Josa::EunNeun), "서울은");
+    }
+
+    #[test]
+    fn test_ending() {
+        assert_eq!(ending("한국"), Ending::Consonant);
+        assert_eq!(ending("『한글』"), Ending::Rieul);
+        assert_eq!(ending("ㅋㅋ"), Ending::Consonant);
+        assert_eq!(ending("ㅏ"), Ending::Vowel);
+        // 結合型字母 (NFD)。
+        assert_eq!(ending("\u{1112}\u{1161}\u{11AB}"), Ending::Consonant);
+        assert_eq!(ending("\u{1112}\u{1161}"), Ending::Vowel);
+        // 数字は漢数詞の読み (삼, 이, 일, 십)。
+        assert_eq!(ending("3"), Ending::Consonant);
+        assert_eq!(ending("2"), Ending::Vowel);
+        assert_eq!(ending("1"), Ending::Rieul);
+        assert_eq!(ending("10"), Ending::Consonant);
+        assert_eq!(ending("KTX"), Ending::Unknown);
+        assert_eq!(ending(""), Ending::Unknown);
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Josa::parse("은/는"), Some(Josa::EunNeun));
+        assert_eq!(Josa::parse("가/이"), Some(Josa::IGa));
+        assert_eq!(Josa::parse("을(를)"), Some(Josa::EulReul));
+        assert_eq!(Josa::parse("(으)로"), Some(Josa::EuroRo));
+        assert_eq!(Josa::parse("로/으로"), Some(Josa::EuroRo));
+        assert_eq!(Josa::parse("에"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        assert_eq!(resolve("사과을/를 먹고 물은(는) 마셨다"), "사과를 먹고 물은 마셨다");
+        assert_eq!(resolve("서울(으)로 부산으로/로"), "서울로 부산으로");
+        assert_eq!(resolve("3개이/가 2이/가"), "3개가 2가");
+        // 読みの分からない文字の後は両方の形。
+        assert_eq!(resolve("KTX을/를"), "KTX을(를)");
+        // 先頭の表記はそのまま。
+        assert_eq!(resolve("은/는"), "은/는");
+    }
+
+    #[test]
+    fn test_expand() {
+        let value = |key: &str| match key {
+            "name" => "김철수".to_string(),
+            "city" => "서울".to_string(),
+            _ => key.to_uppercase(),
+        };
+        assert_eq!(expand("{name}은/는 {city}(으)로 가요", value), "김철수는 서울로 가요");
+        assert_eq!(expand("{x}이/가 {} {y", value), "X이(가) {} {y");
+    }
+}
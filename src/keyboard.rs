@@ -0,0 +1,848 @@
+//! 字母配列入力モジュール。
+//!
+//! キーごとに字母が割り当てられた配列 (2ボル式・3ボル式) の入力を処理する。
+//! ローマ字入力と異なり、1キー = 1字母として字母単位のオートマトンで音節を組み立てる。
+//! キーはUS配列の文字で表す (大文字・記号 = Shift)。
+//!
+//! ## 2ボル式の規則
+//!
+//! - 子音は初声と終声の両方に使う (終声の後に母音が来ると次の音節の初声に移る)
+//! - 複合母音 (ㅘ, ㅙ, ㅚ, ㅝ, ㅞ, ㅟ, ㅢ) と二重終声 (ㄳ, ㄺ など) は続けて打鍵して作る
+//! - 濃音 (ㄲ, ㄸ, ㅃ, ㅆ, ㅉ) と ㅒ, ㅖ は Shift で入力する (大文字で表す)
+//!
+//! ## 3ボル式の規則 (390・最終)
+//!
+//! - 初声・中声・終声がそれぞれ別のキーに割り当てられ、終声は次の音節に移らない
+//! - 同じ初声の連打で濃音 (ㄱ+ㄱ = ㄲ)、終声の連打で二重終声 (ㄹ+ㄱ = ㄺ) を作る
+//! - Shift 層には終声・数字・記号が割り当てられる
+
+use crate::hangul::ComposerUpdate;
+use crate::jamo::{self, Cho, Jong, Jung};
+
+/// 複合母音 (中声, 追加する中声, 合成後の中声)。
+const COMPOUND_JUNGSEONG: [(u32, u32, u32); 7] = [
+    (8, 0, 9),    // ㅗ + ㅏ = ㅘ
+    (8, 1, 10),   // ㅗ + ㅐ = ㅙ
+    (8, 20, 11),  // ㅗ + ㅣ = ㅚ
+    (13, 4, 14),  // ㅜ + ㅓ = ㅝ
+    (13, 5, 15),  // ㅜ + ㅔ = ㅞ
+    (13, 20, 16), // ㅜ + ㅣ = ㅟ
+    (18, 20, 19), // ㅡ + ㅣ = ㅢ
+];
+
+/// 濃音になる初声 (初声, 合成後の初声)。3ボル式で同じ初声を連打したときに使う。
+const DOUBLE_CHOSEONG: [(u32, u32); 5] = [
+    (0, 1),   // ㄱ + ㄱ = ㄲ
+    (3, 4),   // ㄷ + ㄷ = ㄸ
+    (7, 8),   // ㅂ + ㅂ = ㅃ
+    (9, 10),  // ㅅ + ㅅ = ㅆ
+    (12, 13), // ㅈ + ㅈ = ㅉ
+];
+
+/// キー配列。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    /// 2ボル式 (KS X 5002 標準配列)。
+    Dubeolsik,
+    /// 3ボル式 390。
+    Sebeolsik390,
+    /// 3ボル式 最終。
+    SebeolsikFinal,
+}
+
+/// キーに割り当てられた字母。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    /// 子音 (初声インデックス)。初声・終声のどちらにもなる (2ボル式)。
+    Consonant(u32),
+    /// 母音 (中声インデックス)。終声を次の音節に移す (2ボル式)。
+    Vowel(u32),
+    /// 初声 (初声インデックス)。
+    Cho(u32),
+    /// 中声 (中声インデックス)。
+    Jung(u32),
+    /// 終声 (終声インデックス)。
+    Jong(u32),
+    /// 記号・数字 (Shift 層)。
+    Symbol(char),
+}
+
+impl Layout {
+    /// キー (大文字 = Shift) に対応する字母を返す。
+    fn key(&self, c: char) -> Option<Key> {
+        match self {
+            Layout::Dubeolsik => dubeolsik_key(c),
+            Layout::Sebeolsik390 => sebeolsik_key(c, SEBEOLSIK_390_SHIFT),
+            Layout::SebeolsikFinal => sebeolsik_key(c, SEBEOLSIK_FINAL_SHIFT),
+        }
+    }
+
+    /// 配列で処理するキーか判定する。
+    ///
+    /// falseのキーは組み立て中の音節を確定してそのまま出力する。
+    pub fn is_layout_key(&self, c: char) -> bool {
+        self.key(c).is_some()
+    }
+}
+
+/// 2ボル式のキー割り当て。
+///
+/// Shift で別の字母になるキー (濃音, ㅒ, ㅖ) 以外は大文字も小文字と同じ字母になる。
+fn dubeolsik_key(c: char) -> Option<Key> {
+    use Key::{Consonant as C, Vowel as V};
+
+    let key = match c {
+        'Q' => C(8),  // ㅃ
+        'W' => C(13), // ㅉ
+        'E' => C(4),  // ㄸ
+        'R' => C(1),  // ㄲ
+        'T' => C(10), // ㅆ
+        'O' => V(3),  // ㅒ
+        'P' => V(7),  // ㅖ
+        _ => match c.to_ascii_lowercase() {
+            'q' => C(7),  // ㅂ
+            'w' => C(12), // ㅈ
+            'e' => C(3),  // ㄷ
+            'r' => C(0),  // ㄱ
+            't' => C(9),  // ㅅ
+            'y' => V(12), // ㅛ
+            'u' => V(6),  // ㅕ
+            'i' => V(2),  // ㅑ
+            'o' => V(1),  // ㅐ
+            'p' => V(5),  // ㅔ
+            'a' => C(6),  // ㅁ
+            's' => C(2),  // ㄴ
+            'd' => C(11), // ㅇ
+            'f' => C(5),  // ㄹ
+            'g' => C(18), // ㅎ
+            'h' => V(8),  // ㅗ
+            'j' => V(4),  // ㅓ
+            'k' => V(0),  // ㅏ
+            'l' => V(20), // ㅣ
+            'z' => C(15), // ㅋ
+            'x' => C(16), // ㅌ
+            'c' => C(14), // ㅊ
+            'v' => C(17), // ㅍ
+            'b' => V(17), // ㅠ
+            'n' => V(13), // ㅜ
+            'm' => V(18), // ㅡ
+            _ => return None,
+        },
+    };
+    Some(key)
+}
+
+/// 3ボル式の共通キー割り当て (Shift なしの文字キー・数字キー)。
+///
+/// 390 と最終で共通。`/` と `9` は複合母音用の ㅗ・ㅜ。
+const SEBEOLSIK_BASE: [(char, Key); 39] = [
+    ('a', Key::Jong(21)), // ㅇ
+    ('b', Key::Jung(13)), // ㅜ
+    ('c', Key::Jung(5)), // ㅔ
+    ('d', Key::Jung(20)), // ㅣ
+    ('e', Key::Jung(6)), // ㅕ
+    ('f', Key::Jung(0)), // ㅏ
+    ('g', Key::Jung(18)), // ㅡ
+    ('h', Key::Cho(2)), // ㄴ
+    ('i', Key::Cho(6)), // ㅁ
+    ('j', Key::Cho(11)), // ㅇ
+    ('k', Key::Cho(0)), // ㄱ
+    ('l', Key::Cho(12)), // ㅈ
+    ('m', Key::Cho(18)), // ㅎ
+    ('n', Key::Cho(9)), // ㅅ
+    ('o', Key::Cho(14)), // ㅊ
+    ('p', Key::Cho(17)), // ㅍ
+    ('q', Key::Jong(19)), // ㅅ
+    ('r', Key::Jung(1)), // ㅐ
+    ('s', Key::Jong(4)), // ㄴ
+    ('t', Key::Jung(4)), // ㅓ
+    ('u', Key::Cho(3)), // ㄷ
+    ('v', Key::Jung(8)), // ㅗ
+    ('w', Key::Jong(8)), // ㄹ
+    ('x', Key::Jong(1)), // ㄱ
+    ('y', Key::Cho(5)), // ㄹ
+    ('z', Key::Jong(16)), // ㅁ
+    (';', Key::Cho(7)), // ㅂ
+    ('\'', Key::Cho(16)), // ㅌ
+    ('/', Key::Jung(8)), // ㅗ
+    ('0', Key::Cho(15)), // ㅋ
+    ('1', Key::Jong(27)), // ㅎ
+    ('2', Key::Jong(20)), // ㅆ
+    ('3', Key::Jong(17)), // ㅂ
+    ('4', Key::Jung(12)), // ㅛ
+    ('5', Key::Jung(17)), // ㅠ
+    ('6', Key::Jung(2)), // ㅑ
+    ('7', Key::Jung(7)), // ㅖ
+    ('8', Key::Jung(19)), // ㅢ
+    ('9', Key::Jung(13)), // ㅜ
+];
+
+/// 3ボル式 390 の Shift 層。
+const SEBEOLSIK_390_SHIFT: &[(char, Key)] = &[
+    ('!', Key::Jong(22)), // ㅈ
+    ('A', Key::Jong(7)),  // ㄷ
+    ('B', Key::Symbol('!')),
+    ('C', Key::Jong(10)), // ㄻ
+    ('D', Key::Jong(9)),  // ㄺ
+    ('E', Key::Jong(24)), // ㅋ
+    ('F', Key::Jong(2)),  // ㄲ
+    ('G', Key::Symbol('/')),
+    ('H', Key::Symbol('\'')),
+    ('I', Key::Symbol('8')),
+    ('J', Key::Symbol('4')),
+    ('K', Key::Symbol('5')),
+    ('L', Key::Symbol('6')),
+    ('M', Key::Symbol('1')),
+    ('N', Key::Symbol('0')),
+    ('O', Key::Symbol('9')),
+    ('P', Key::Symbol('>')),
+    ('Q', Key::Jong(26)), // ㅍ
+    ('R', Key::Jung(3)), // ㅒ
+    ('S', Key::Jong(6)),  // ㄶ
+    ('T', Key::Symbol(';')),
+    ('U', Key::Symbol('7')),
+    ('V', Key::Jong(15)), // ㅀ
+    ('W', Key::Jong(25)), // ㅌ
+    ('X', Key::Jong(18)), // ㅄ
+    ('Y', Key::Symbol('<')),
+    ('Z', Key::Jong(23)), // ㅊ
+    ('<', Key::Symbol('2')),
+    ('>', Key::Symbol('3')),
+];
+
+/// 3ボル式 最終の Shift 層。
+const SEBEOLSIK_FINAL_SHIFT: &[(char, Key)] = &[
+    ('!', Key::Jong(2)),  // ㄲ
+    ('"', Key::Symbol('·')),
+    ('#', Key::Jong(22)), // ㅈ
+    ('$', Key::Jong(14)), // ㄿ
+    ('%', Key::Jong(13)), // ㄾ
+    ('&', Key::Symbol('“')),
+    ('(', Key::Symbol('\'')),
+    (')', Key::Symbol('~')),
+    ('*', Key::Symbol('”')),
+    ('-', Key::Symbol(')')),
+    (':', Key::Symbol('4')),
+    ('<', Key::Symbol(',')),
+    ('=', Key::Symbol('>')),
+    ('>', Key::Symbol('.')),
+    ('?', Key::Symbol('!')),
+    ('@', Key::Jong(9)),  // ㄺ
+    ('A', Key::Jong(7)),  // ㄷ
+    ('B', Key::Symbol('?')),
+    ('C', Key::Jong(24)), // ㅋ
+    ('D', Key::Jong(11)), // ㄼ
+    ('E', Key::Jong(5)),  // ㄵ
+    ('F', Key::Jong(10)), // ㄻ
+    ('G', Key::Jung(3)), // ㅒ
+    ('H', Key::Symbol('0')),
+    ('I', Key::Symbol('7')),
+    ('J', Key::Symbol('1')),
+    ('K', Key::Symbol('2')),
+    ('L', Key::Symbol('3')),
+    ('M', Key::Symbol('"')),
+    ('N', Key::Symbol('-')),
+    ('O', Key::Symbol('8')),
+    ('P', Key::Symbol('9')),
+    ('Q', Key::Jong(26)), // ㅍ
+    ('R', Key::Jong(15)), // ㅀ
+    ('S', Key::Jong(6)),  // ㄶ
+    ('T', Key::Jong(12)), // ㄽ
+    ('U', Key::Symbol('6')),
+    ('V', Key::Jong(3)),  // ㄳ
+    ('W', Key::Jong(25)), // ㅌ
+    ('X', Key::Jong(18)), // ㅄ
+    ('Y', Key::Symbol('5')),
+    ('Z', Key::Jong(23)), // ㅊ
+    ('[', Key::Symbol('(')),
+    ('\\', Key::Symbol(':')),
+    (']', Key::Symbol('<')),
+    ('^', Key::Symbol('=')),
+    ('_', Key::Symbol(';')),
+    ('`', Key::Symbol('*')),
+    ('{', Key::Symbol('%')),
+    ('|', Key::Symbol('\\')),
+    ('}', Key::Symbol('/')),
+    ('~', Key::Symbol('※')),
+];
+
+/// 3ボル式のキー割り当て。Shift 層を先に検索する。
+fn sebeolsik_key(c: char, shift: &[(char, Key)]) -> Option<Key> {
+    shift
+        .iter()
+        .chain(SEBEOLSIK_BASE.iter())
+        .find(|&&(k, _)| k == c)
+        .map(|&(_, key)| key)
+        // 記号と同じ文字を出すキーは配列で処理しない。
+        .filter(|&key| key != Key::Symbol(c))
+}
+
+/// 組み立て中の音節。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Syllable {
+    cho: Option<u32>,
+    jung: Option<u32>,
+    jong: Option<u32>,
+}
+
+impl Syllable {
+    fn is_empty(&self) -> bool {
+        self.cho.is_none() && self.jung.is_none() && self.jong.is_none()
+    }
+
+    /// 音節を文字列にする。
+    ///
+    /// 初声と中声が揃えば完成形ハングル、それ以外は字母ごとの互換字母になる。
+    fn render(&self, out: &mut String) {
+        match (self.cho, self.jung) {
+            (Some(cho), Some(jung)) => {
+                out.push(jamo::compose_index(cho, jung, self.jong.unwrap_or(0)));
+            }
+            _ => {
+                if let Some(cho) = self.cho.and_then(Cho::new) {
+                    out.push(cho.to_compat());
+                }
+                if let Some(jung) = self.jung.and_then(Jung::new) {
+                    out.push(jung.to_compat());
+                }
+                if let Some(jong) = self.jong.and_then(Jong::new) {
+                    out.push(jong.to_compat());
+                }
+            }
+        }
+    }
+}
+
+/// 初声インデックスを終声インデックスに変換する。
+fn choseong_to_jongseong(cho: u32) -> Option<u32> {
+    Cho::new(cho)?.to_jong().map(Jong::index)
+}
+
+/// 終声インデックスを初声インデックスに戻す。
+fn jongseong_to_choseong(jong: u32) -> Option<u32> {
+    Jong::new(jong)?.to_cho().map(Cho::index)
+}
+
+/// 二重終声を (残る終声, 次の初声) に分ける。
+fn split_jongseong(jong: u32) -> Option<(u32, u32)> {
+    let (first, second) = Jong::new(jong)?.split()?;
+    Some((first.index(), second.index()))
+}
+
+/// 終声に子音 (初声インデックス) を加えた二重終声を返す。
+fn add_to_jongseong(jong: u32, cho: u32) -> Option<u32> {
+    Jong::new(jong)?.combine(Cho::new(cho)?).map(Jong::index)
+}
+
+/// 2つの終声を二重終声に合成する (3ボル式)。
+fn combine_jongseong(first: u32, second: u32) -> Option<u32> {
+    match (first, second) {
+        (1, 1) => return Some(2),   // ㄱ + ㄱ = ㄲ
+        (19, 19) => return Some(20), // ㅅ + ㅅ = ㅆ
+        _ => {}
+    }
+    add_to_jongseong(first, jongseong_to_choseong(second)?)
+}
+
+/// 字母配列の逐次入力オートマトン。
+///
+/// 1キーずつ受け取り、完成した音節を確定済み文字列に移し、
+/// 組み立て中の音節をプリエディットとして保持する。
+pub struct KeyboardComposer {
+    /// キー配列。
+    layout: Layout,
+    /// 入力されたキー。
+    input: String,
+    /// 確定済みの文字列。
+    committed: String,
+    /// 組み立て中の音節。
+    syllable: Syllable,
+    /// 各入力キーの直前の状態 (音節, `committed` の長さ)。pop で復元する。
+    history: Vec<(Syllable, usize)>,
+}
+
+impl KeyboardComposer {
+    /// 新しいオートマトンを作成する。
+    pub fn new(layout: Layout) -> Self {
+        Self {
+            layout,
+            input: String::new(),
+            committed: String::new(),
+            syllable: Syllable::default(),
+            history: Vec::new(),
+        }
+    }
+
+    /// キーを1つ入力する。
+    ///
+    /// 配列にないキー (数字・記号・スペースなど) は組み立て中の音節を確定してそのまま出力する。
+    pub fn push(&mut self, c: char) -> ComposerUpdate {
+        let committed_len = self.committed.len();
+        self.history.push((self.syllable, committed_len));
+        self.input.push(c);
+
+        match self.layout.key(c) {
+            Some(Key::Consonant(cho)) => self.push_consonant(cho),
+            Some(Key::Vowel(jung)) => self.push_vowel(jung),
+            Some(Key::Cho(cho)) => self.push_cho(cho),
+            Some(Key::Jung(jung)) => self.push_jung(jung),
+            Some(Key::Jong(jong)) => self.push_jong(jong),
+            Some(Key::Symbol(symbol)) => {
+                self.flush();
+                self.committed.push(symbol);
+            }
+            None => {
+                self.flush();
+                self.committed.push(c);
+            }
+        }
+
+        ComposerUpdate {
+            retracted: String::new(),
+            committed: self.committed[committed_len..].to_string(),
+            preedit: self.preedit(),
+        }
+    }
+
+    /// 子音を入力する。
+    fn push_consonant(&mut self, cho: u32) {
+        let s = self.syllable;
+        match (s.cho, s.jung, s.jong) {
+            // 初声+中声 → 終声にできれば終声。
+            (Some(_), Some(_), None) if choseong_to_jongseong(cho).is_some() => {
+                self.syllable.jong = choseong_to_jongseong(cho);
+            }
+            // 終声あり → 二重終声にできれば合成。
+            (Some(_), Some(_), Some(jong)) => {
+                match add_to_jongseong(jong, cho) {
+                    Some(compound) => self.syllable.jong = Some(compound),
+                    None => self.start_syllable(Some(cho), None),
+                }
+            }
+            // 空 → 初声。
+            (None, None, _) => self.syllable.cho = Some(cho),
+            // それ以外 → 確定して新しい音節の初声。
+            _ => self.start_syllable(Some(cho), None),
+        }
+    }
+
+    /// 母音を入力する。
+    fn push_vowel(&mut self, jung: u32) {
+        let s = self.syllable;
+        match (s.jung, s.jong) {
+            // 中声なし → 中声。
+            (None, _) => self.syllable.jung = Some(jung),
+            // 終声なし → 複合母音にできれば合成。
+            (Some(prev), None) => {
+                match COMPOUND_JUNGSEONG.iter().find(|&&(p, n, _)| p == prev && n == jung) {
+                    Some(&(_, _, compound)) => self.syllable.jung = Some(compound),
+                    None => self.start_syllable(None, Some(jung)),
+                }
+            }
+            // 終声あり → 終声 (二重終声は後ろの子音) を次の音節の初声に移す。
+            (Some(_), Some(jong)) => {
+                let (remain, moved) = match split_jongseong(jong) {
+                    Some((first, second)) => (Some(first), second),
+                    None => (None, jongseong_to_choseong(jong).unwrap_or(11)),
+                };
+                self.syllable.jong = remain;
+                self.start_syllable(Some(moved), Some(jung));
+            }
+        }
+    }
+
+    /// 初声を入力する (3ボル式)。
+    fn push_cho(&mut self, cho: u32) {
+        let s = self.syllable;
+        match (s.cho, s.jung, s.jong) {
+            (None, None, None) => self.syllable.cho = Some(cho),
+            // 同じ初声の連打 → 濃音。
+            (Some(prev), None, None) if prev == cho => {
+                match DOUBLE_CHOSEONG.iter().find(|&&(single, _)| single == cho) {
+                    Some(&(_, double)) => self.syllable.cho = Some(double),
+                    None => self.start_syllable(Some(cho), None),
+                }
+            }
+            _ => self.start_syllable(Some(cho), None),
+        }
+    }
+
+    /// 中声を入力する (3ボル式)。
+    fn push_jung(&mut self, jung: u32) {
+        let s = self.syllable;
+        match (s.jung, s.jong) {
+            (None, None) => self.syllable.jung = Some(jung),
+            (Some(prev), None) => {
+                match COMPOUND_JUNGSEONG.iter().find(|&&(p, n, _)| p == prev && n == jung) {
+                    Some(&(_, _, compound)) => self.syllable.jung = Some(compound),
+                    None => self.start_syllable(None, Some(jung)),
+                }
+            }
+            // 終声の後の中声は新しい音節 (終声は移動しない)。
+            _ => self.start_syllable(None, Some(jung)),
+        }
+    }
+
+    /// 終声を入力する (3ボル式)。
+    fn push_jong(&mut self, jong: u32) {
+        let s = self.syllable;
+        match (s.cho, s.jung, s.jong) {
+            (Some(_), Some(_), None) => self.syllable.jong = Some(jong),
+            // 終声の連打 → 二重終声。
+            (Some(_), Some(_), Some(prev)) => match combine_jongseong(prev, jong) {
+                Some(compound) => self.syllable.jong = Some(compound),
+                None => self.start_jong(jong),
+            },
+            _ => self.start_jong(jong),
+        }
+    }
+
+    /// 組み立て中の音節を確定し、終声のみの新しい音節を始める。
+    fn start_jong(&mut self, jong: u32) {
+        self.flush();
+        self.syllable = Syllable { cho: None, jung: None, jong: Some(jong) };
+    }
+
+    /// 組み立て中の音節を確定し、新しい音節を始める。
+    fn start_syllable(&mut self, cho: Option<u32>, jung: Option<u32>) {
+        self.flush();
+        self.syllable = Syllable { cho, jung, jong: None };
+    }
+
+    /// 組み立て中の音節を確定済み文字列に移す。
+    fn flush(&mut self) {
+        if !self.syllable.is_empty() {
+            self.syllable.render(&mut self.committed);
+            self.syllable = Syllable::default();
+        }
+    }
+
+    /// 最後に入力したキーを取り消し、未確定に戻した部分と新しいプリエディットを返す。
+    ///
+    /// 入力が空の場合はNoneを返す。
+    pub fn pop(&mut self) -> Option<ComposerUpdate> {
+        let (syllable, committed_len) = self.history.pop()?;
+        self.input.pop();
+        self.syllable = syllable;
+        let retracted = self.committed.split_off(committed_len);
+        Some(ComposerUpdate {
+            retracted,
+            committed: String::new(),
+            preedit: self.preedit(),
+        })
+    }
+
+    /// 組み立て中の音節を確定して返し、状態をリセットする。
+    pub fn commit(&mut self) -> String {
+        let preedit = self.preedit();
+        self.clear();
+        preedit
+    }
+
+    /// 状態をリセットする。
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.committed.clear();
+        self.syllable = Syllable::default();
+        self.history.clear();
+    }
+
+    /// 確定済みの文字列。
+    pub fn committed(&self) -> &str {
+        &self.committed
+    }
+
+    /// 組み立て中の音節を返す。
+    pub fn preedit(&self) -> String {
+        let mut preedit = String::new();
+        self.syllable.render(&mut preedit);
+        preedit
+    }
+
+    /// 確定済みと組み立て中を合わせた文字列を返す。
+    pub fn text(&self) -> String {
+        let mut text = self.committed.clone();
+        self.syllable.render(&mut text);
+        text
+    }
+
+    /// キー配列を返す。
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+
+    /// 入力されたキーを返す。
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// 入力が空か判定する。
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+}
+
+/// 字母配列の一括変換器。
+///
+/// キー列 (大文字 = Shift) をまとめてハングルに変換する。
+pub struct KeyboardConverter {
+    layout: Layout,
+}
+
+impl KeyboardConverter {
+    /// 新しい変換器を作成する。
+    pub fn new(layout: Layout) -> Self {
+        Self { layout }
+    }
+
+    /// キー列をハングルに変換する。
+    pub fn convert(&self, input: &str) -> String {
+        let mut composer = KeyboardComposer::new(self.layout);
+        for c in input.chars() {
+            composer.push(c);
+        }
+        composer.text()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dubeolsik(input: &str) -> String {
+        KeyboardConverter::new(Layout::Dubeolsik).convert(input)
+    }
+
+    #[test]
+    fn test_dubeolsik_words() {
+        assert_eq!(dubeolsik("gksrmf"), "한글");
+        assert_eq!(dubeolsik("dkssudgktpdy"), "안녕하세요");
+        assert_eq!(dubeolsik("rkatkgkqslek"), "감사합니다");
+        assert_eq!(dubeolsik("eogksalsrnr"), "대한민국");
+    }
+
+    #[test]
+    fn test_dubeolsik_shift() {
+        // Shift (大文字) で濃音・ㅒ・ㅖ。
+        assert_eq!(dubeolsik("Rk"), "까");
+        assert_eq!(dubeolsik("Ek"), "따");
+        assert_eq!(dubeolsik("Qk"), "빠");
+        assert_eq!(dubeolsik("Tk"), "싸");
+        assert_eq!(dubeolsik("Wk"), "짜");
+        assert_eq!(dubeolsik("dO"), "얘");
+        assert_eq!(dubeolsik("dP"), "예");
+        // Shift の割り当てがないキーは小文字と同じ。
+        assert_eq!(dubeolsik("GK"), "하");
+        // 濃音終声。
+        assert_eq!(dubeolsik("rkT"), "갔");
+    }
+
+    #[test]
+    fn test_dubeolsik_compound_vowels() {
+        assert_eq!(dubeolsik("dhk"), "와");
+        assert_eq!(dubeolsik("dho"), "왜");
+        assert_eq!(dubeolsik("dhl"), "외");
+        assert_eq!(dubeolsik("dnj"), "워");
+        assert_eq!(dubeolsik("dnp"), "웨");
+        assert_eq!(dubeolsik("dnl"), "위");
+        assert_eq!(dubeolsik("dml"), "의");
+        assert_eq!(dubeolsik("rhkdlf"), "과일");
+    }
+
+    #[test]
+    fn test_dubeolsik_compound_finals() {
+        assert_eq!(dubeolsik("ekfr"), "닭");
+        assert_eq!(dubeolsik("qkqt"), "밦");
+        assert_eq!(dubeolsik("djqtek"), "없다");
+        assert_eq!(dubeolsik("dksgdk"), "않아");
+        assert_eq!(dubeolsik("tkfa"), "삶");
+    }
+
+    #[test]
+    fn test_dubeolsik_final_moves_to_next_syllable() {
+        // 終声 + 母音 → 終声が次の音節の初声に移る。
+        assert_eq!(dubeolsik("rkr"), "각");
+        assert_eq!(dubeolsik("rkrk"), "가가");
+        assert_eq!(dubeolsik("rnrdj"), "국어");
+        // 二重終声は後ろの子音だけが移る。
+        assert_eq!(dubeolsik("ekfrdl"), "닭이");
+        assert_eq!(dubeolsik("ekfrl"), "달기");
+        assert_eq!(dubeolsik("djqtdj"), "없어");
+        assert_eq!(dubeolsik("djqtj"), "업서");
+    }
+
+    #[test]
+    fn test_dubeolsik_isolated_jamo() {
+        // 単独の子音・母音は互換字母。
+        assert_eq!(dubeolsik("zzz"), "ㅋㅋㅋ");
+        assert_eq!(dubeolsik("gg"), "ㅎㅎ");
+        assert_eq!(dubeolsik("rt"), "ㄱㅅ");
+        assert_eq!(dubeolsik("k"), "ㅏ");
+        assert_eq!(dubeolsik("kk"), "ㅏㅏ");
+        assert_eq!(dubeolsik("hk"), "ㅘ");
+        // 終声にならない子音 (ㄸ) は次の音節へ。
+        assert_eq!(dubeolsik("rkE"), "가ㄸ");
+    }
+
+    #[test]
+    fn test_dubeolsik_passthrough() {
+        assert_eq!(dubeolsik(""), "");
+        assert_eq!(dubeolsik("gks1"), "한1");
+        assert_eq!(dubeolsik("gks rmf"), "한 글");
+        assert_eq!(dubeolsik("123!"), "123!");
+    }
+
+    #[test]
+    fn test_composer_backspace() {
+        // Backspace は字母単位で取り消す。
+        let mut composer = KeyboardComposer::new(Layout::Dubeolsik);
+        for c in "ekfr".chars() {
+            composer.push(c);
+        }
+        assert_eq!(composer.text(), "닭");
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("달".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("다".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("ㄷ".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("".to_string()));
+        assert_eq!(composer.pop(), None);
+    }
+
+    #[test]
+    fn test_composer_commit_on_next_syllable() {
+        let mut composer = KeyboardComposer::new(Layout::Dubeolsik);
+        composer.push('g');
+        composer.push('k');
+        let update = composer.push('s');
+        assert_eq!(update.committed, "");
+        assert_eq!(update.preedit, "한");
+
+        // 次の子音で音節が確定。
+        let update = composer.push('r');
+        assert_eq!(update.committed, "한");
+        assert_eq!(update.preedit, "ㄱ");
+
+        // 終声が移る場合も確定。
+        composer.push('n');
+        composer.push('r');
+        let update = composer.push('j');
+        assert_eq!(update.committed, "구");
+        assert_eq!(update.preedit, "거");
+
+        // 取り消すと確定前に戻る。
+        let update = composer.pop().unwrap();
+        assert_eq!(update.retracted, "구");
+        assert_eq!(update.preedit, "국");
+        assert_eq!(composer.committed(), "한");
+
+        assert_eq!(composer.commit(), "국");
+        assert!(composer.is_empty());
+    }
+
+    fn sebeolsik390(input: &str) -> String {
+        KeyboardConverter::new(Layout::Sebeolsik390).convert(input)
+    }
+
+    fn sebeolsik_final(input: &str) -> String {
+        KeyboardConverter::new(Layout::SebeolsikFinal).convert(input)
+    }
+
+    #[test]
+    fn test_sebeolsik_words() {
+        for sebeolsik in [sebeolsik390, sebeolsik_final] {
+            assert_eq!(sebeolsik("mfskgw"), "한글");
+            assert_eq!(sebeolsik("jfsheamfncj4"), "안녕하세요");
+            assert_eq!(sebeolsik("kfznfmf3hduf"), "감사합니다");
+            assert_eq!(sebeolsik("urmfsidskbx"), "대한민국");
+            assert_eq!(sebeolsik("urmfsidsk9x"), "대한민국");
+        }
+    }
+
+    #[test]
+    fn test_sebeolsik_final_does_not_move() {
+        // 終声は次の音節に移らない (2ボル式との違い)。
+        assert_eq!(sebeolsik390("kbxjt"), "국어");
+        assert_eq!(sebeolsik390("kbxt"), "국ㅓ");
+        assert_eq!(sebeolsik390("kfx"), "각");
+    }
+
+    #[test]
+    fn test_sebeolsik_double_consonants() {
+        // 同じ初声の連打で濃音。
+        assert_eq!(sebeolsik390("kkf"), "까");
+        assert_eq!(sebeolsik390("uuf"), "따");
+        assert_eq!(sebeolsik390(";;f"), "빠");
+        assert_eq!(sebeolsik390("nnf"), "싸");
+        assert_eq!(sebeolsik390("llf"), "짜");
+        // 濃音にならない初声の連打は別の音節。
+        assert_eq!(sebeolsik390("hhf"), "ㄴ나");
+        // 終声の連打で二重終声。
+        assert_eq!(sebeolsik390("ufwx"), "닭");
+        assert_eq!(sebeolsik390("jt3q"), "없");
+        assert_eq!(sebeolsik390("jfs1"), "않");
+        assert_eq!(sebeolsik390("kfxx"), "갂");
+        assert_eq!(sebeolsik390("kfqq"), "갔");
+    }
+
+    #[test]
+    fn test_sebeolsik_compound_vowels() {
+        // ㅗ・ㅜ は左手 (v, b) と右手 (/, 9) のどちらからでも合成できる。
+        assert_eq!(sebeolsik390("jvf"), "와");
+        assert_eq!(sebeolsik390("j/f"), "와");
+        assert_eq!(sebeolsik390("j9t"), "워");
+        assert_eq!(sebeolsik390("jbd"), "위");
+        assert_eq!(sebeolsik390("jgd"), "의");
+        assert_eq!(sebeolsik390("j8"), "의");
+    }
+
+    #[test]
+    fn test_sebeolsik_shift_layers() {
+        // 390: Shift で終声・数字。
+        assert_eq!(sebeolsik390("kfF"), "갂");
+        assert_eq!(sebeolsik390("ufD"), "닭");
+        assert_eq!(sebeolsik390("jR"), "얘");
+        assert_eq!(sebeolsik390("JKL"), "456");
+        assert_eq!(sebeolsik390("kf!"), "갖");
+        assert_eq!(sebeolsik390("a.b"), "ㅇ.ㅜ");
+        // 最終: Shift で終声・数字・記号。
+        assert_eq!(sebeolsik_final("kf!"), "갂");
+        assert_eq!(sebeolsik_final("kf#"), "갖");
+        assert_eq!(sebeolsik_final("jG"), "얘");
+        assert_eq!(sebeolsik_final("JKL"), "123");
+        assert_eq!(sebeolsik_final("kfV"), "갃");
+        assert_eq!(sebeolsik_final("\"~"), "·※");
+        // 記号を出すキーは音節を確定する。
+        assert_eq!(sebeolsik_final("kf?"), "가!");
+    }
+
+    #[test]
+    fn test_sebeolsik_isolated_jamo() {
+        assert_eq!(sebeolsik390("0"), "ㅋ");
+        assert_eq!(sebeolsik390("f"), "ㅏ");
+        assert_eq!(sebeolsik390("s"), "ㄴ");
+        assert_eq!(sebeolsik390("kfsx"), "간ㄱ");
+    }
+
+    #[test]
+    fn test_sebeolsik_backspace() {
+        let mut composer = KeyboardComposer::new(Layout::Sebeolsik390);
+        for c in "ufwx".chars() {
+            composer.push(c);
+        }
+        assert_eq!(composer.text(), "닭");
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("달".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("다".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("ㄷ".to_string()));
+        assert_eq!(composer.pop().map(|u| u.preedit), Some("".to_string()));
+    }
+
+    #[test]
+    fn test_is_layout_key() {
+        assert!(Layout::Dubeolsik.is_layout_key('a'));
+        assert!(!Layout::Dubeolsik.is_layout_key(';'));
+        assert!(Layout::Sebeolsik390.is_layout_key(';'));
+        assert!(Layout::Sebeolsik390.is_layout_key('9'));
+        assert!(!Layout::Sebeolsik390.is_layout_key('.'));
+        assert!(Layout::SebeolsikFinal.is_layout_key('~'));
+        assert!(!Layout::SebeolsikFinal.is_layout_key('.'));
+    }
+}
@@ -5,6 +5,19 @@
 //! 設定ディレクトリ移行、候補ウィンドウ修正、インストーラー改善)。
 
 pub mod hangul;
+pub mod hanja;
+pub mod jamo;
+pub mod keyboard;
+pub mod normalize;
+pub mod spelling;
+pub mod numeral;
+pub mod palette;
+pub mod diagnostic;
+pub mod josa;
+pub mod choseong;
+pub mod romaja_table;
+pub mod romanize;
+mod trie;
 pub mod config;
 pub mod logger;
 pub mod user_dict;
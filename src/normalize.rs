@@ -0,0 +1,214 @@
+//! ハングル文字列の出力形式 (正規化形式) の相互変換モジュール。
+//!
+//! 同じ音節を次の3つの形式で表現できる。
+//!
+//! - NFC: 完成形ハングル (한)。古語の字母を含む音節は完成形にならないため結合型字母の列
+//! - NFD: 結合型字母の列 (ᄒ ᅡ ᆫ)。macOS のファイル名などで使われる
+//! - 互換字母: 互換字母の列 (ㅎㅏㄴ)。字母を単独で表示する用途向け
+//!
+//! 互換字母は初声と終声の区別がないため、互換字母の列から音節を組み立てる場合は
+//! 子音 + 母音で音節を始め、直後に母音が続かない子音を終声とする (ㅎㅏㄴㅏ → 하나)。
+//! 母音が続かない互換字母 (ㅋㅋ, ㅠㅠ) はそのまま残す。
+
+use crate::jamo;
+
+/// 出力形式。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OutputForm {
+    /// 完成形ハングル (既定)。
+    #[default]
+    Nfc,
+    /// 結合型字母の列。
+    Nfd,
+    /// 互換字母の列。
+    Compat,
+}
+
+impl OutputForm {
+    /// すべての出力形式。
+    pub const ALL: [OutputForm; 3] = [OutputForm::Nfc, OutputForm::Nfd, OutputForm::Compat];
+
+    /// 名前 (nfc, nfd, compat) から出力形式を取得する。
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|form| form.name() == name)
+    }
+
+    /// 出力形式の名前を返す。
+    pub fn name(self) -> &'static str {
+        match self {
+            OutputForm::Nfc => "nfc",
+            OutputForm::Nfd => "nfd",
+            OutputForm::Compat => "compat",
+        }
+    }
+}
+
+/// ハングルを含む文字列を指定した出力形式に変換する。
+///
+/// 完成形ハングル・結合型字母の列・互換字母の列のいずれの入力も受け付け、
+/// ハングル以外の文字はそのまま出力する。
+pub fn normalize(text: &str, form: OutputForm) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut pos = 0;
+
+    while pos < chars.len() {
+        match read_syllable(&chars, pos) {
+            Some((cho, jung, jong, end)) => {
+                push_syllable(cho, jung, jong, form, &mut out);
+                pos = end;
+            }
+            None => {
+                push_jamo(chars[pos], form, &mut out);
+                pos += 1;
+            }
+        }
+    }
+
+    out
+}
+
+/// 古語を含むインデックスの音節を出力形式に従って `out` に追加する。
+pub(crate) fn push_syllable(cho: u32, jung: u32, jong: u32, form: OutputForm, out: &mut String) {
+    match form {
+        OutputForm::Nfc => jamo::push_syllable(cho, jung, jong, out),
+        OutputForm::Nfd => {
+            out.push(jamo::choseong_char(cho).unwrap_or('?'));
+            out.push(jamo::jungseong_char(jung).unwrap_or('?'));
+            if jong != 0 {
+                out.push(jamo::jongseong_char(jong).unwrap_or('?'));
+            }
+        }
+        OutputForm::Compat => {
+            // 互換字母のない古語の字母は結合型字母のまま。
+            let cho = jamo::choseong_compat(cho).or_else(|| jamo::choseong_char(cho));
+            let jung = jamo::jungseong_compat(jung).or_else(|| jamo::jungseong_char(jung));
+            out.push(cho.unwrap_or('?'));
+            out.push(jung.unwrap_or('?'));
+            if jong != 0 {
+                let jong = jamo::jongseong_compat(jong).or_else(|| jamo::jongseong_char(jong));
+                out.push(jong.unwrap_or('?'));
+            }
+        }
+    }
+}
+
+/// 音節にならない1文字を出力形式に従って `out` に追加する。
+///
+/// 互換字母の形式では単独の結合型字母を互換字母にする。それ以外はそのまま。
+fn push_jamo(c: char, form: OutputForm, out: &mut String) {
+    let compat = match form {
+        OutputForm::Compat => jamo::choseong_index(c)
+            .and_then(jamo::choseong_compat)
+            .or_else(|| jamo::jungseong_index(c).and_then(jamo::jungseong_compat))
+            .or_else(|| jamo::jongseong_index(c).and_then(jamo::jongseong_compat)),
+        OutputForm::Nfc | OutputForm::Nfd => None,
+    };
+    out.push(compat.unwrap_or(c));
+}
+
+/// `pos` から始まる1音節を読み取り、(初声, 中声, 終声, 終了位置) を返す。
+///
+/// 完成形ハングル (直後の結合型終声を含む)、結合型字母の列、互換字母の列を音節として扱う。
+fn read_syllable(chars: &[char], pos: usize) -> Option<(u32, u32, u32, usize)> {
+    let c = chars[pos];
+    let next = chars.get(pos + 1).copied();
+
+    if let Some((cho, jung, jong)) = jamo::decompose(c) {
+        let jong = jong.map_or(0, |j| j.index());
+        // 終声のない完成形 + 結合型終声 (가 + ᆨ) は1音節。
+        if jong == 0 {
+            if let Some(jong) = next.and_then(jamo::jongseong_index) {
+                return Some((cho.index(), jung.index(), jong, pos + 2));
+            }
+        }
+        return Some((cho.index(), jung.index(), jong, pos + 1));
+    }
+
+    if let Some(cho) = jamo::choseong_index(c) {
+        let jung = next.and_then(jamo::jungseong_index)?;
+        return match chars.get(pos + 2).copied().and_then(jamo::jongseong_index) {
+            Some(jong) => Some((cho, jung, jong, pos + 3)),
+            None => Some((cho, jung, 0, pos + 2)),
+        };
+    }
+
+    let cho = jamo::choseong_index_from_compat(c)?;
+    let jung = next.and_then(jamo::jungseong_index_from_compat)?;
+    // 直後に母音が続く子音は次の音節の初声。
+    let jong = chars.get(pos + 2).copied().and_then(|j| {
+        let vowel_follows = chars.get(pos + 3).is_some_and(|&v| jamo::jungseong_index_from_compat(v).is_some());
+        let starts_syllable = vowel_follows && jamo::choseong_index_from_compat(j).is_some();
+        jamo::jongseong_index_from_compat(j).filter(|_| !starts_syllable)
+    });
+    match jong {
+        Some(jong) => Some((cho, jung, jong, pos + 3)),
+        None => Some((cho, jung, 0, pos + 2)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_forms() {
+        let nfc = "한국어";
+        let nfd = "\u{1112}\u{1161}\u{11AB}\u{1100}\u{116E}\u{11A8}\u{110B}\u{1165}";
+        let compat = "ㅎㅏㄴㄱㅜㄱㅇㅓ";
+
+        for input in [nfc, nfd, compat] {
+            assert_eq!(normalize(input, OutputForm::Nfc), nfc, "input: {:?}", input);
+            assert_eq!(normalize(input, OutputForm::Nfd), nfd, "input: {:?}", input);
+            assert_eq!(normalize(input, OutputForm::Compat), compat, "input: {:?}", input);
+        }
+    }
+
+    #[test]
+    fn test_normalize_compound_jamo() {
+        assert_eq!(normalize("읽고 과자", OutputForm::Compat), "ㅇㅣㄺㄱㅗ ㄱㅘㅈㅏ");
+        assert_eq!(normalize("ㅇㅣㄺㄱㅗ ㄱㅘㅈㅏ", OutputForm::Nfc), "읽고 과자");
+        // 母音が続く子音は次の音節の初声。
+        assert_eq!(normalize("ㅎㅏㄴㅏ", OutputForm::Nfc), "하나");
+        assert_eq!(normalize("ㅎㅏㄴㅇㅏ", OutputForm::Nfc), "한아");
+    }
+
+    #[test]
+    fn test_normalize_keeps_other_text() {
+        assert_eq!(normalize("ㅋㅋㅋ ㅠㅠ abc", OutputForm::Nfc), "ㅋㅋㅋ ㅠㅠ abc");
+        assert_eq!(normalize("ㅋㅋㅋ ㅠㅠ abc", OutputForm::Nfd), "ㅋㅋㅋ ㅠㅠ abc");
+        // 単独の結合型字母は互換字母の形式でのみ変わる。
+        assert_eq!(normalize("\u{1100}", OutputForm::Nfc), "\u{1100}");
+        assert_eq!(normalize("\u{1100}\u{11AB}", OutputForm::Compat), "ㄱㄴ");
+        // 完成形 + 結合型終声は1音節にまとめる。
+        assert_eq!(normalize("가\u{11A8}", OutputForm::Nfc), "각");
+    }
+
+    #[test]
+    fn test_normalize_archaic() {
+        let nfd = "\u{1112}\u{119E}\u{11AB}";
+        assert_eq!(normalize(nfd, OutputForm::Nfc), nfd);
+        assert_eq!(normalize(nfd, OutputForm::Compat), "ㅎㆍㄴ");
+        assert_eq!(normalize("ㅎㆍㄴ", OutputForm::Nfd), nfd);
+        // 互換字母のない古語の字母は結合型字母のまま。
+        assert_eq!(normalize("\u{110B}\u{D7B0}", OutputForm::Compat), "ㅇ\u{D7B0}");
+    }
+
+    #[test]
+    fn test_normalize_roundtrip_all_syllables() {
+        for code in jamo::SYLLABLE_BASE..=jamo::SYLLABLE_LAST {
+            let s = char::from_u32(code).unwrap().to_string();
+            for form in OutputForm::ALL {
+                assert_eq!(normalize(&normalize(&s, form), OutputForm::Nfc), s, "form: {:?}", form);
+            }
+        }
+    }
+
+    #[test]
+    fn test_output_form_names() {
+        for form in OutputForm::ALL {
+            assert_eq!(OutputForm::from_name(form.name()), Some(form));
+        }
+        assert_eq!(OutputForm::from_name("nfkc"), None);
+    }
+}
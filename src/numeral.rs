@@ -0,0 +1,278 @@
+//! 数字の韓国語読み (数詞) 変換モジュール。
+//!
+//! 数字を次の読み方に変換する。
+//!
+//! - 漢数詞: 백이십삼。万単位 (만, 억, 조, 경) ごとに分かち書きする (일억 이천만)
+//! - 固有数詞: 열셋。1〜99 のみ
+//! - 数字と単位の混用: 1억 2천만
+//! - 助数詞付き: 세 개, 두 명 (固有数詞をとる助数詞), 삼 년 (漢数詞をとる助数詞), 유월
+
+/// 漢数詞の 0〜9。
+const SINO_DIGITS: [&str; 10] = ["영", "일", "이", "삼", "사", "오", "육", "칠", "팔", "구"];
+
+/// 4桁内の位 (一・十・百・千)。
+const SINO_PLACES: [&str; 4] = ["", "십", "백", "천"];
+
+/// 万単位の位 (一・万・億・兆・京)。
+const LARGE_UNITS: [&str; 5] = ["", "만", "억", "조", "경"];
+
+/// 固有数詞の 1〜9。
+const NATIVE_ONES: [&str; 10] = ["", "하나", "둘", "셋", "넷", "다섯", "여섯", "일곱", "여덟", "아홉"];
+
+/// 固有数詞の 1〜9 の冠形詞形 (助数詞の前の形)。
+const NATIVE_PRENOUN_ONES: [&str; 10] = ["", "한", "두", "세", "네", "다섯", "여섯", "일곱", "여덟", "아홉"];
+
+/// 固有数詞の 10〜90。
+const NATIVE_TENS: [&str; 10] = ["", "열", "스물", "서른", "마흔", "쉰", "예순", "일흔", "여든", "아흔"];
+
+/// 固有数詞をとる助数詞。それ以外の助数詞は漢数詞をとる。
+pub const NATIVE_COUNTERS: [&str; 22] = [
+    "개", "명", "마리", "살", "시", "시간", "권", "잔", "병", "장", "대",
+    "번", "번째", "사람", "그릇", "켤레", "벌", "채", "송이", "가지", "군데", "달",
+];
+
+/// 数字の列 (区切りのカンマを含んでよい) を数値にする。
+///
+/// 数字以外の文字を含む場合や u64 の範囲を超える場合はNoneを返す。
+pub fn parse(digits: &str) -> Option<u64> {
+    let digits: String = digits.chars().filter(|&c| c != ',').collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+/// 漢数詞の読みを返す (123 → 백이십삼, 120000000 → 일억 이천만)。
+///
+/// 十・百・千の前の 1 と、先頭の万の前の 1 は読まない (십, 백, 만)。
+pub fn sino(n: u64) -> String {
+    if n == 0 {
+        return SINO_DIGITS[0].to_string();
+    }
+    let mut words = Vec::new();
+    for (unit, group) in groups(n).into_iter().rev() {
+        if group == 0 {
+            continue;
+        }
+        let mut word = sino_group(group);
+        // 先頭の 만 (일만) は 1 を読まない (억 の後の 일만 は読む)。
+        if unit == 1 && group == 1 && words.is_empty() {
+            word.clear();
+        }
+        word.push_str(LARGE_UNITS[unit]);
+        words.push(word);
+    }
+    words.join(" ")
+}
+
+/// 固有数詞の読みを返す (13 → 열셋)。1〜99 以外はNone。
+pub fn native(n: u64) -> Option<String> {
+    native_with(n, &NATIVE_ONES)
+}
+
+/// 固有数詞の冠形詞形 (助数詞の前の形) を返す (3 → 세, 20 → 스무, 21 → 스물한)。1〜99 以外はNone。
+pub fn native_prenoun(n: u64) -> Option<String> {
+    if n == 20 {
+        return Some("스무".to_string());
+    }
+    native_with(n, &NATIVE_PRENOUN_ONES)
+}
+
+/// 数字と単位を混ぜた表記を返す (120000000 → 1억 2천만, 12345 → 1만 2345)。
+///
+/// 万単位ごとに数字で書き、千の倍数は「2천」のように単位で書く。
+pub fn mixed(n: u64) -> String {
+    if n == 0 {
+        return "0".to_string();
+    }
+    let mut words = Vec::new();
+    for (unit, group) in groups(n).into_iter().rev() {
+        if group == 0 {
+            continue;
+        }
+        let mut word = match group {
+            1000 | 2000 | 3000 | 4000 | 5000 | 6000 | 7000 | 8000 | 9000 => format!("{}천", group / 1000),
+            _ => group.to_string(),
+        };
+        word.push_str(LARGE_UNITS[unit]);
+        words.push(word);
+    }
+    words.join(" ")
+}
+
+/// 固有数詞をとる助数詞か判定する。
+pub fn is_native_counter(counter: &str) -> bool {
+    NATIVE_COUNTERS.contains(&counter)
+}
+
+/// 助数詞を付けた読みを返す (3, 개 → 세 개, 3, 년 → 삼 년, 6, 월 → 유월)。
+///
+/// 固有数詞をとる助数詞では 99 までを固有数詞で、100 以上は百の位までを漢数詞で読む
+/// (123, 개 → 백스물세 개)。번째 の 1 は 첫 (첫 번째)。
+/// 월 は数詞に続けて書き、6월 → 유월, 10월 → 시월 と読む。それ以外は数詞と助数詞の間を空ける。
+pub fn with_counter(n: u64, counter: &str) -> String {
+    if counter == "월" {
+        let number = match n {
+            6 => "유".to_string(),
+            10 => "시".to_string(),
+            _ => sino(n),
+        };
+        return format!("{}월", number);
+    }
+    if !is_native_counter(counter) {
+        return format!("{} {}", sino(n), counter);
+    }
+
+    let number = match (n, n % 100) {
+        (1, _) if counter == "번째" => "첫".to_string(),
+        (0, _) => sino(0),
+        (_, 0) => sino(n),
+        (_, rest) if n < 100 => native_prenoun(rest).unwrap_or_default(),
+        (_, rest) => format!("{}{}", sino(n - rest), native_prenoun(rest).unwrap_or_default()),
+    };
+    format!("{} {}", number, counter)
+}
+
+/// 数値の読みの候補を返す (重複なし)。
+///
+/// 助数詞が空なら 漢数詞・固有数詞・数字と単位の混用 (数字のままと同じなら除く) の順。
+/// 助数詞があれば助数詞付きの読み。
+pub fn readings(n: u64, counter: &str) -> Vec<String> {
+    if !counter.is_empty() {
+        return vec![with_counter(n, counter)];
+    }
+    let mut readings = vec![sino(n)];
+    readings.extend(native(n));
+    let mixed = mixed(n);
+    if mixed != n.to_string() {
+        readings.push(mixed);
+    }
+    readings
+}
+
+/// 数値を下位から万単位の (位, 4桁の値) に分ける。
+fn groups(mut n: u64) -> Vec<(usize, u64)> {
+    let mut groups = Vec::new();
+    let mut unit = 0;
+    while n > 0 {
+        groups.push((unit, n % 10000));
+        n /= 10000;
+        unit += 1;
+    }
+    groups
+}
+
+/// 4桁 (1〜9999) の漢数詞の読みを返す。
+fn sino_group(group: u64) -> String {
+    let mut word = String::new();
+    for place in (0..4).rev() {
+        let digit = (group / 10u64.pow(place as u32) % 10) as usize;
+        if digit == 0 {
+            continue;
+        }
+        if digit != 1 || place == 0 {
+            word.push_str(SINO_DIGITS[digit]);
+        }
+        word.push_str(SINO_PLACES[place]);
+    }
+    word
+}
+
+/// 1〜99 の固有数詞を、一の位の表 (数詞または冠形詞形) で組み立てる。
+fn native_with(n: u64, ones: &[&str; 10]) -> Option<String> {
+    if !(1..=99).contains(&n) {
+        return None;
+    }
+    let (tens, one) = ((n / 10) as usize, (n % 10) as usize);
+    Some(format!("{}{}", NATIVE_TENS[tens], ones[one]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sino() {
+        let cases = [
+            (0, "영"),
+            (1, "일"),
+            (10, "십"),
+            (11, "십일"),
+            (123, "백이십삼"),
+            (1111, "천백십일"),
+            (2026, "이천이십육"),
+            (10000, "만"),
+            (12345, "만 이천삼백사십오"),
+            (20000, "이만"),
+            (100_000_000, "일억"),
+            (120_000_000, "일억 이천만"),
+            (100_010_000, "일억 일만"),
+            (3_0000_0000_0005, "삼조 오"),
+            (u64::MAX, "천팔백사십사경 육천칠백사십사조 칠백삼십칠억 구백오십오만 천육백십오"),
+        ];
+        for (n, expected) in cases {
+            assert_eq!(sino(n), expected, "n: {}", n);
+        }
+    }
+
+    #[test]
+    fn test_native() {
+        assert_eq!(native(1).as_deref(), Some("하나"));
+        assert_eq!(native(13).as_deref(), Some("열셋"));
+        assert_eq!(native(20).as_deref(), Some("스물"));
+        assert_eq!(native(99).as_deref(), Some("아흔아홉"));
+        assert_eq!(native(0), None);
+        assert_eq!(native(100), None);
+
+        assert_eq!(native_prenoun(3).as_deref(), Some("세"));
+        assert_eq!(native_prenoun(20).as_deref(), Some("스무"));
+        assert_eq!(native_prenoun(21).as_deref(), Some("스물한"));
+        assert_eq!(native_prenoun(44).as_deref(), Some("마흔네"));
+    }
+
+    #[test]
+    fn test_mixed() {
+        assert_eq!(mixed(0), "0");
+        assert_eq!(mixed(123), "123");
+        assert_eq!(mixed(3000), "3천");
+        assert_eq!(mixed(12345), "1만 2345");
+        assert_eq!(mixed(120_000_000), "1억 2천만");
+        assert_eq!(mixed(305_000_000), "3억 500만");
+    }
+
+    #[test]
+    fn test_with_counter() {
+        let cases = [
+            (3, "개", "세 개"),
+            (2, "명", "두 명"),
+            (20, "살", "스무 살"),
+            (21, "살", "스물한 살"),
+            (1, "번째", "첫 번째"),
+            (2, "번째", "두 번째"),
+            (100, "개", "백 개"),
+            (123, "개", "백스물세 개"),
+            (3, "년", "삼 년"),
+            (30, "분", "삼십 분"),
+            (6, "월", "유월"),
+            (10, "월", "시월"),
+            (12, "월", "십이월"),
+            (10000, "원", "만 원"),
+        ];
+        for (n, counter, expected) in cases {
+            assert_eq!(with_counter(n, counter), expected, "n: {}, counter: {}", n, counter);
+        }
+    }
+
+    #[test]
+    fn test_readings_and_parse() {
+        assert_eq!(readings(13, ""), vec!["십삼", "열셋"]);
+        assert_eq!(readings(3000, ""), vec!["삼천", "3천"]);
+        assert_eq!(readings(3, "개"), vec!["세 개"]);
+
+        assert_eq!(parse("1,234"), Some(1234));
+        assert_eq!(parse("007"), Some(7));
+        assert_eq!(parse(""), None);
+        assert_eq!(parse("12a"), None);
+        assert_eq!(parse("99999999999999999999"), None);
+    }
+}
@@ -0,0 +1,299 @@
+//! 記号パレットモジュール。
+//!
+//! 韓国語IMEの記号入力 (子音 + 漢字キー) の記号表と、絵文字のショートコード表を提供する。
+//!
+//! - 記号表: KS X 1001 の特殊文字の並びに従い、子音ごとに記号をまとめる
+//!   (ㄱ = 文章記号, ㄴ = 括弧, ㄷ = 数学記号, ㅁ = 図形 ※☆★ など)
+//! - 絵文字: `:smile:` → 😄。JSON ファイル (`{"entries": {"smile": "😄"}}`) で追加・置換できる
+//!
+//! どちらも候補 ([`PaletteCandidate`]) の一覧として返し、候補ウィンドウやCLIで表示する。
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+/// 標準の記号表 (1行に「子音:グループ名:記号の並び」)。
+const STANDARD_GROUPS: &str = include_str!("data/symbol_palette.txt");
+
+/// 標準の絵文字のショートコード表。
+const STANDARD_EMOJI: &str = include_str!("data/emoji.json");
+
+/// 絵文字のショートコード表JSONのルート構造。
+#[derive(Deserialize)]
+struct EmojiTableJson {
+    entries: BTreeMap<String, String>,
+}
+
+/// 子音に割り当てた記号のグループ。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolGroup {
+    /// 子音 (互換字母)。
+    pub consonant: char,
+    /// グループ名 (문장부호, 괄호 など)。
+    pub name: String,
+    /// 記号 (KS X 1001 の順)。
+    pub symbols: Vec<char>,
+}
+
+/// 記号パレットの候補。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PaletteCandidate {
+    /// 候補を選んだときに入力する文字列。
+    pub text: String,
+    /// 候補ウィンドウに表示する文字列 (絵文字はショートコード付き: 「😄 :smile:」)。
+    pub label: String,
+}
+
+/// 記号パレット (子音ごとの記号表と絵文字のショートコード表)。
+pub struct SymbolPalette {
+    /// 子音ごとの記号のグループ (記号表の順)。
+    groups: Vec<SymbolGroup>,
+    /// ショートコード (コロンなし) → 絵文字。
+    emoji: BTreeMap<String, String>,
+}
+
+impl SymbolPalette {
+    /// 空のパレットを作成する。
+    pub fn empty() -> Self {
+        Self { groups: Vec::new(), emoji: BTreeMap::new() }
+    }
+
+    /// 標準の記号表と絵文字のショートコード表のパレットを作成する。
+    pub fn standard() -> Self {
+        let mut palette = Self::empty();
+        palette.extend_groups(STANDARD_GROUPS);
+        palette
+            .extend_emoji_json(STANDARD_EMOJI)
+            .expect("標準の絵文字のショートコード表が不正");
+        palette
+    }
+
+    /// 記号表のテキスト (1行に「子音:グループ名:記号の並び」、`#` で始まる行と空行は無視) を追加する。
+    ///
+    /// 既にある子音の行は記号を末尾に追加する (重複は除く)。
+    pub fn extend_groups(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut fields = line.splitn(3, ':');
+            let (Some(consonant), Some(name), Some(symbols)) = (fields.next(), fields.next(), fields.next()) else {
+                continue;
+            };
+            let mut consonant = consonant.chars();
+            let (Some(consonant), None) = (consonant.next(), consonant.next()) else {
+                continue;
+            };
+            let index = match self.groups.iter().position(|g| g.consonant == consonant) {
+                Some(index) => index,
+                None => {
+                    self.groups.push(SymbolGroup { consonant, name: name.to_string(), symbols: Vec::new() });
+                    self.groups.len() - 1
+                }
+            };
+            let group = &mut self.groups[index];
+            for symbol in symbols.chars().filter(|c| !c.is_whitespace()) {
+                if !group.symbols.contains(&symbol) {
+                    group.symbols.push(symbol);
+                }
+            }
+        }
+    }
+
+    /// 絵文字のショートコード表JSON (`{"entries": {"smile": "😄"}}`) を追加する。
+    ///
+    /// 同じショートコードは置き換える。ショートコードが空・コロンや空白を含む、
+    /// または絵文字が空の場合はエラー。追加した項目数を返す。
+    pub fn extend_emoji_json(&mut self, json: &str) -> Result<usize> {
+        let table: EmojiTableJson = serde_json::from_str(json).context("絵文字のショートコード表のJSONが不正")?;
+        for (shortcode, emoji) in &table.entries {
+            if shortcode.is_empty() || shortcode.contains(':') || shortcode.contains(char::is_whitespace) {
+                bail!("不正なショートコード: {:?}", shortcode);
+            }
+            if emoji.is_empty() {
+                bail!("ショートコード {:?} の絵文字が空", shortcode);
+            }
+        }
+        let count = table.entries.len();
+        self.emoji.extend(table.entries);
+        Ok(count)
+    }
+
+    /// 絵文字のショートコード表ファイルを追加する。
+    pub fn extend_emoji_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("絵文字のショートコード表を読み込めません: {}", path.display()))?;
+        self.extend_emoji_json(&content)
+            .with_context(|| format!("絵文字のショートコード表: {}", path.display()))?;
+        Ok(())
+    }
+
+    /// 記号のグループを記号表の順に返す。
+    pub fn groups(&self) -> &[SymbolGroup] {
+        &self.groups
+    }
+
+    /// 子音 (互換字母) に割り当てた記号のグループを返す。
+    pub fn group(&self, consonant: char) -> Option<&SymbolGroup> {
+        self.groups.iter().find(|g| g.consonant == consonant)
+    }
+
+    /// ショートコード (前後のコロンは省略可) の絵文字を返す。
+    pub fn emoji(&self, shortcode: &str) -> Option<&str> {
+        self.emoji.get(shortcode.trim_matches(':')).map(String::as_str)
+    }
+
+    /// ショートコードが前方一致する絵文字の候補を最大 `limit` 件返す。
+    ///
+    /// 前後のコロンは無視し、大文字は小文字として扱う。完全一致を先頭に、残りはショートコード順。
+    pub fn emoji_candidates(&self, prefix: &str, limit: usize) -> Vec<PaletteCandidate> {
+        let prefix = prefix.trim_matches(':').to_lowercase();
+        if prefix.is_empty() {
+            return Vec::new();
+        }
+        let exact = self.emoji.get_key_value(&prefix);
+        let others = self
+            .emoji
+            .range(prefix.clone()..)
+            .take_while(|(shortcode, _)| shortcode.starts_with(&prefix))
+            .filter(|(shortcode, _)| **shortcode != prefix);
+        exact
+            .into_iter()
+            .chain(others)
+            .take(limit)
+            .map(|(shortcode, emoji)| PaletteCandidate {
+                text: emoji.clone(),
+                label: format!("{} :{}:", emoji, shortcode),
+            })
+            .collect()
+    }
+
+    /// 記号パレットの候補を最大 `limit` 件返す。
+    ///
+    /// `key` が子音 (互換字母) 1文字ならその記号のグループ、それ以外は絵文字のショートコードの前方一致。
+    pub fn candidates(&self, key: &str, limit: usize) -> Vec<PaletteCandidate> {
+        let mut chars = key.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            if let Some(group) = self.group(c) {
+                return group
+                    .symbols
+                    .iter()
+                    .take(limit)
+                    .map(|s| PaletteCandidate { text: s.to_string(), label: s.to_string() })
+                    .collect();
+            }
+        }
+        self.emoji_candidates(key, limit)
+    }
+
+    /// 文字列中の `:ショートコード:` を絵文字に置き換える。表にないショートコードはそのまま。
+    pub fn replace_shortcodes(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(start) = rest.find(':') {
+            out.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let emoji = after.find(':').and_then(|end| Some((end, self.emoji.get(&after[..end])?)));
+            match emoji {
+                Some((end, emoji)) => {
+                    out.push_str(emoji);
+                    rest = &after[end + 1..];
+                }
+                None => {
+                    out.push(':');
+                    rest = after;
+                }
+            }
+        }
+        out.push_str(rest);
+        out
+    }
+}
+
+impl Default for SymbolPalette {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_groups() {
+        let palette = SymbolPalette::standard();
+        let consonants: String = palette.groups().iter().map(|g| g.consonant).collect();
+        assert_eq!(consonants, "ㄱㄴㄷㄹㅁㅂㅅㅇㅈㅊㅋㅌㅍㅎㄲㄸㅃㅆ");
+
+        let group = palette.group('ㅁ').unwrap();
+        assert_eq!(group.name, "도형");
+        assert!(group.symbols.starts_with(&['＃', '＆', '＊', '＠', '§', '※', '☆', '★']));
+        assert!(palette.group('ㄷ').unwrap().symbols.contains(&'≠'));
+        assert_eq!(palette.group('ㅅ').unwrap().symbols[0], '㉠');
+        assert_eq!(palette.group('ㅈ').unwrap().symbols.len(), 30);
+        assert_eq!(palette.group('ㅎ').unwrap().symbols.len(), 48);
+        assert!(palette.group('ㅏ').is_none());
+    }
+
+    #[test]
+    fn test_candidates() {
+        let palette = SymbolPalette::standard();
+        let candidates = palette.candidates("ㅁ", 3);
+        let texts: Vec<&str> = candidates.iter().map(|c| c.text.as_str()).collect();
+        assert_eq!(texts, ["＃", "＆", "＊"]);
+        assert_eq!(candidates[0].label, "＃");
+
+        let candidates = palette.candidates(":s", 20);
+        assert!(candidates.len() > 3);
+        assert!(candidates.iter().all(|c| c.label.contains(":s")));
+        assert_eq!(palette.candidates("smile", 9)[0], PaletteCandidate {
+            text: "😄".to_string(),
+            label: "😄 :smile:".to_string(),
+        });
+        assert_eq!(palette.candidates(":Heart", 2)[0].text, "❤️");
+        assert_eq!(palette.candidates("heart", 9).len(), 2);
+        assert!(palette.candidates("zzz", 9).is_empty());
+        assert!(palette.candidates("::", 9).is_empty());
+        assert!(palette.candidates("ㅁ", 0).is_empty());
+    }
+
+    #[test]
+    fn test_emoji() {
+        let mut palette = SymbolPalette::standard();
+        assert_eq!(palette.emoji(":smile:"), Some("😄"));
+        assert_eq!(palette.emoji("fire"), Some("🔥"));
+        assert_eq!(palette.emoji("nothing"), None);
+
+        assert_eq!(palette.replace_shortcodes("좋아요 :thumbsup: :x: 12:30 :nope:"), "좋아요 👍 ❌ 12:30 :nope:");
+        assert_eq!(palette.replace_shortcodes("a:fire::tada:b"), "a🔥🎉b");
+
+        let json = r#"{ "entries": { "smile": "🙂", "kimchi": "🥬" } }"#;
+        assert_eq!(palette.extend_emoji_json(json).unwrap(), 2);
+        assert_eq!(palette.emoji("smile"), Some("🙂"));
+        assert_eq!(palette.emoji("kimchi"), Some("🥬"));
+
+        assert!(palette.extend_emoji_json(r#"{ "entries": { "a b": "x" } }"#).is_err());
+        assert!(palette.extend_emoji_json(r#"{ "entries": { "a:b": "x" } }"#).is_err());
+        assert!(palette.extend_emoji_json(r#"{ "entries": { "ab": "" } }"#).is_err());
+        assert!(palette.extend_emoji_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_extend_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("emoji.json");
+        fs::write(&path, r#"{ "entries": { "chamsae": "🐦" } }"#).unwrap();
+
+        let mut palette = SymbolPalette::standard();
+        palette.extend_emoji_file(&path).unwrap();
+        assert_eq!(palette.candidates(":chams", 9)[0].text, "🐦");
+        assert!(palette.extend_emoji_file(&dir.path().join("missing.json")).is_err());
+
+        let mut palette = SymbolPalette::empty();
+        palette.extend_groups("# comment\nㅁ:도형:※☆\nㅁ:도형:☆★\nx\n");
+        assert_eq!(palette.group('ㅁ').unwrap().symbols, ['※', '☆', '★']);
+    }
+}
@@ -0,0 +1,812 @@
+//! ローマ字マッピングテーブル。
+//!
+//! ローマ字→ハングル変換で使う初声・中声・終声のテーブルを提供する。
+//! 標準テーブル (国語のローマ字表記法に近い綴り) と McCune–Reischauer 式のテーブルに加え、
+//! JSONファイルでテーブルを拡張・置換できる。
+//!
+//! ```json
+//! {
+//!   "choseong": { "entries": { "q": "ㄲ" } },
+//!   "jungseong": { "entries": { "oo": "ㅜ", "ee": "ㅣ" } },
+//!   "jongseong": { "mode": "replace", "entries": { "k": 1, "n": 4 } },
+//!   "symbols": { "entries": { "->": "→" } }
+//! }
+//! ```
+//!
+//! - `mode`: `"extend"` (元のテーブルに追加・上書き、既定) または `"replace"` (置換)
+//! - `entries`: ローマ字 → 字母 (互換字母 `"ㄲ"` またはインデックス `1`)。
+//!   `symbols` は記号の綴り → 出力する文字列 (記号入力を有効にした場合のみ使用)
+//!
+//! 字母の不正・インデックスの範囲外・不正なキーはエラーとし、ファイル全体を採用しない。
+//! 元のテーブルの上書きや、初声と中声で同じキーを定義した場合は競合として報告する。
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::jamo::{self, Cho, Jong, Jung};
+
+/// 字母の種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JamoKind {
+    /// 初声。
+    Choseong,
+    /// 中声。
+    Jungseong,
+    /// 終声。
+    Jongseong,
+}
+
+impl JamoKind {
+    /// JSONでのテーブル名を返す。
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Choseong => "choseong",
+            Self::Jungseong => "jungseong",
+            Self::Jongseong => "jongseong",
+        }
+    }
+
+    /// インデックスを検証し、表示用の互換字母を返す。
+    fn jamo(&self, index: u32) -> Option<char> {
+        match self {
+            Self::Choseong => Cho::new(index).map(Cho::to_compat),
+            Self::Jungseong => Jung::new(index).map(Jung::to_compat),
+            Self::Jongseong => Jong::new(index).map(Jong::to_compat),
+        }
+    }
+
+    /// 互換字母からインデックスを取得する。
+    fn index_of(&self, jamo: char) -> Option<u32> {
+        match self {
+            Self::Choseong => Cho::from_compat(jamo).map(Cho::index),
+            Self::Jungseong => Jung::from_compat(jamo).map(Jung::index),
+            Self::Jongseong => Jong::from_compat(jamo).map(Jong::index),
+        }
+    }
+}
+
+/// テーブル定義の競合。
+///
+/// 変換は可能だが、意図しない結果になりうる定義を表す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TableConflict {
+    /// 元のテーブルのエントリを別の字母で上書きした。
+    Override {
+        kind: JamoKind,
+        key: String,
+        base: u32,
+        custom: u32,
+    },
+    /// 同じキーが初声と中声の両方にある (初声が優先される)。
+    Ambiguous { key: String },
+    /// 元の記号テーブルのエントリを別の文字列で上書きした。
+    SymbolOverride {
+        key: String,
+        base: String,
+        custom: String,
+    },
+}
+
+impl fmt::Display for TableConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Override { kind, key, base, custom } => write!(
+                f,
+                "{}: \"{}\" は既定の {} を {} で上書きします",
+                kind.name(),
+                key,
+                kind.jamo(*base).unwrap_or('?'),
+                kind.jamo(*custom).unwrap_or('?'),
+            ),
+            Self::Ambiguous { key } => write!(
+                f,
+                "\"{}\" は初声と中声の両方に定義されています (初声が優先されます)",
+                key
+            ),
+            Self::SymbolOverride { key, base, custom } => {
+                write!(f, "symbols: \"{}\" は既定の {} を {} で上書きします", key, base, custom)
+            }
+        }
+    }
+}
+
+/// テーブルの適用方法。
+#[derive(Deserialize, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum TableMode {
+    /// 元のテーブルに追加する。
+    #[default]
+    Extend,
+    /// 元のテーブルを置き換える。
+    Replace,
+}
+
+/// JSONでの字母の指定 (互換字母またはインデックス)。
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JamoJson {
+    Index(u32),
+    Jamo(String),
+}
+
+/// JSONの1テーブル定義。
+#[derive(Deserialize)]
+struct TableJson {
+    #[serde(default)]
+    mode: TableMode,
+    #[serde(default)]
+    entries: HashMap<String, JamoJson>,
+}
+
+/// JSONの記号テーブル定義。
+#[derive(Deserialize)]
+struct SymbolTableJson {
+    #[serde(default)]
+    mode: TableMode,
+    #[serde(default)]
+    entries: HashMap<String, String>,
+}
+
+/// JSONテーブルファイルのルート構造。
+#[derive(Deserialize)]
+struct TablesJson {
+    choseong: Option<TableJson>,
+    jungseong: Option<TableJson>,
+    jongseong: Option<TableJson>,
+    symbols: Option<SymbolTableJson>,
+}
+
+/// ローマ字マッピングテーブル一式。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RomajaTables {
+    /// 初声(子音)マッピング。
+    pub choseong: HashMap<String, u32>,
+    /// 中声(母音)マッピング。
+    pub jungseong: HashMap<String, u32>,
+    /// 終声(パッチム)マッピング。
+    pub jongseong: HashMap<String, u32>,
+    /// 両唇音 (ㅁ, ㅂ, ㅃ, ㅍ) の直後で中声マッピングより優先するマッピング。
+    pub labial_jungseong: HashMap<String, u32>,
+    /// 音節区切りとして読み捨てる文字 (キーの一部にならない場合のみ)。
+    pub separators: Vec<char>,
+    /// 記号の綴り → 記号 (記号入力を有効にした場合のみ使用)。
+    pub symbols: HashMap<String, String>,
+}
+
+impl RomajaTables {
+    /// 標準テーブルを返す。
+    pub fn standard() -> Self {
+        Self {
+            choseong: build_choseong(),
+            jungseong: build_jungseong(),
+            jongseong: build_jongseong(),
+            labial_jungseong: HashMap::new(),
+            separators: Vec::new(),
+            symbols: build_symbols(),
+        }
+    }
+
+    /// McCune–Reischauer 式のテーブルを返す。
+    ///
+    /// 半月符 (ŏ, ŭ) と ASCII 代替 (o^, u^)、アポストロフィの激音 (k', t', p', ch') を受け付ける。
+    /// 有声音の綴り (g, d, b, j) も平音として扱う。
+    /// 激音にならないアポストロフィは音節区切り (han'guk → 한국) として読み捨てる。
+    pub fn mccune_reischauer() -> Self {
+        Self {
+            choseong: build_mr_choseong(),
+            jungseong: build_mr_jungseong(),
+            jongseong: build_mr_jongseong(),
+            labial_jungseong: HashMap::new(),
+            separators: vec!['\''],
+            symbols: build_symbols(),
+        }
+    }
+
+    /// Yale 式のテーブルを返す。
+    ///
+    /// ㅓ = e, ㅡ = u, ㅜ = wu, ㅐ = ay, ㅔ = ey のように母音を綴り、ㅈ・ㅊ は c・ch と書く。
+    /// 両唇音の直後では ㅜ を u と書く慣例 (mul → 물) に従う。
+    /// 綴りは形態音素的 (ilkta → 읽다) なので、終声は正書法どおりの字母で受け付ける。
+    pub fn yale() -> Self {
+        Self {
+            choseong: build_yale_choseong(),
+            jungseong: build_yale_jungseong(),
+            jongseong: build_yale_jongseong(),
+            labial_jungseong: [("u".to_string(), 13)].into_iter().collect(),
+            separators: Vec::new(),
+            symbols: build_symbols(),
+        }
+    }
+
+    /// 古語の字母 (ㆍ, ㅿ, ㆁ, ㆆ など) の綴りを追加したテーブルを返す。
+    ///
+    /// 既存のキーは上書きしない。綴りは [`ARCHAIC_CHOSEONG`]・[`ARCHAIC_JUNGSEONG`]・
+    /// [`ARCHAIC_JONGSEONG`] を参照。
+    pub fn with_archaic(mut self) -> Self {
+        let tables = [
+            (&mut self.choseong, &ARCHAIC_CHOSEONG[..], jamo::choseong_index as fn(char) -> Option<u32>),
+            (&mut self.jungseong, &ARCHAIC_JUNGSEONG[..], jamo::jungseong_index),
+            (&mut self.jongseong, &ARCHAIC_JONGSEONG[..], jamo::jongseong_index),
+        ];
+        for (table, spellings, index_of) in tables {
+            for &(key, jamo) in spellings {
+                if let Some(index) = index_of(jamo) {
+                    table.entry(key.to_string()).or_insert(index);
+                }
+            }
+        }
+        self
+    }
+
+    /// 大文字 (Shift) で始まる綴りを追加したテーブルを返す。
+    ///
+    /// 子音は先頭を重ねたキー (g → gg) の字母を、母音は先頭に y を付けたキー
+    /// (a → ya, eo → yeo) の字母を、先頭を大文字にしたキー (G → ㄲ, Eo → ㅕ) に割り当てる。
+    /// 対応するキーがない綴りは追加しない。既存のキーは上書きしない。
+    ///
+    /// 2ボル式のキー位置 (O → ㅒ, P → ㅖ) ではなくローマ字の綴りに従うため、`O` は ㅛ、`P` は ㅃ になる。
+    /// ㅒ・ㅖ は `Ae`・`E` で入力する。
+    pub fn with_shift_variants(mut self) -> Self {
+        fn add_variants(table: &mut HashMap<String, u32>, shifted: fn(char, &str) -> String) {
+            let variants: Vec<(String, u32)> = table
+                .keys()
+                .filter_map(|key| {
+                    let first = key.chars().next().filter(|c| c.is_lowercase())?;
+                    let index = *table.get(&shifted(first, key))?;
+                    let upper: String = first.to_uppercase().chain(key.chars().skip(1)).collect();
+                    Some((upper, index))
+                })
+                .collect();
+            for (key, index) in variants {
+                table.entry(key).or_insert(index);
+            }
+        }
+
+        let doubled: fn(char, &str) -> String = |first, key| format!("{}{}", first, key);
+        let glided: fn(char, &str) -> String = |_, key| format!("y{}", key);
+        add_variants(&mut self.choseong, doubled);
+        add_variants(&mut self.jongseong, doubled);
+        add_variants(&mut self.jungseong, glided);
+        add_variants(&mut self.labial_jungseong, glided);
+        self
+    }
+
+    /// JSON文字列から標準テーブルを拡張・置換したテーブルを作成する。
+    ///
+    /// 競合 (標準の上書きなど) は戻り値で報告する。不正な定義はエラーを返す。
+    pub fn from_json(json: &str) -> Result<(Self, Vec<TableConflict>)> {
+        Self::standard().extend_json(json)
+    }
+
+    /// JSON文字列でこのテーブルを拡張・置換する。
+    ///
+    /// 競合 (既定のエントリの上書きなど) は戻り値で報告する。不正な定義はエラーを返す。
+    pub fn extend_json(self, json: &str) -> Result<(Self, Vec<TableConflict>)> {
+        let json: TablesJson = serde_json::from_str(json).context("テーブルJSONの解析に失敗しました")?;
+        let mut tables = self;
+        let mut conflicts = Vec::new();
+
+        let definitions = [
+            (JamoKind::Choseong, json.choseong),
+            (JamoKind::Jungseong, json.jungseong),
+            (JamoKind::Jongseong, json.jongseong),
+        ];
+        for (kind, definition) in definitions {
+            if let Some(definition) = definition {
+                let table = tables.table_mut(kind);
+                apply_table(kind, definition, table, &mut conflicts)?;
+            }
+        }
+        if let Some(definition) = json.symbols {
+            apply_symbol_table(definition, &mut tables.symbols, &mut conflicts)?;
+        }
+
+        let mut ambiguous: Vec<&String> = tables
+            .choseong
+            .keys()
+            .filter(|key| tables.jungseong.contains_key(*key))
+            .collect();
+        ambiguous.sort();
+        conflicts.extend(ambiguous.into_iter().map(|key| TableConflict::Ambiguous { key: key.clone() }));
+
+        Ok((tables, conflicts))
+    }
+
+    /// JSONファイルからテーブルを読み込む (標準テーブルを拡張・置換する)。
+    pub fn load(path: &Path) -> Result<(Self, Vec<TableConflict>)> {
+        Self::standard().extend_file(path)
+    }
+
+    /// JSONファイルでこのテーブルを拡張・置換する。
+    pub fn extend_file(self, path: &Path) -> Result<(Self, Vec<TableConflict>)> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("テーブルファイルを読み込めません: {}", path.display()))?;
+        self.extend_json(&content)
+    }
+
+    /// JSONファイルでこのテーブルを拡張・置換する。失敗した場合はそのまま返す。
+    ///
+    /// 競合と読み込みエラーはログに出力する。
+    pub fn with_file(self, path: &Path) -> Self {
+        match self.clone().extend_file(path) {
+            Ok((tables, conflicts)) => {
+                for conflict in &conflicts {
+                    log::warn!("Romaja table conflict: {}", conflict);
+                }
+                log::info!("Romaja table loaded: {}", path.display());
+                tables
+            }
+            Err(e) => {
+                log::warn!("Failed to load romaja table: {:#}", e);
+                self
+            }
+        }
+    }
+
+    /// 種類に対応するテーブルを返す。
+    fn table_mut(&mut self, kind: JamoKind) -> &mut HashMap<String, u32> {
+        match kind {
+            JamoKind::Choseong => &mut self.choseong,
+            JamoKind::Jungseong => &mut self.jungseong,
+            JamoKind::Jongseong => &mut self.jongseong,
+        }
+    }
+}
+
+impl Default for RomajaTables {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// テーブル定義を検証して適用する。
+fn apply_table(
+    kind: JamoKind,
+    definition: TableJson,
+    table: &mut HashMap<String, u32>,
+    conflicts: &mut Vec<TableConflict>,
+) -> Result<()> {
+    let mut entries = Vec::with_capacity(definition.entries.len());
+    for (key, value) in definition.entries {
+        if key.is_empty() || key.chars().any(|c| c.is_whitespace() || c.is_uppercase()) {
+            bail!("{}: 不正なキー \"{}\" (空白・大文字は使用できません)", kind.name(), key);
+        }
+        let index = match value {
+            JamoJson::Index(index) => {
+                if kind.jamo(index).is_none() {
+                    bail!("{}: \"{}\" のインデックス {} は範囲外です", kind.name(), key, index);
+                }
+                index
+            }
+            JamoJson::Jamo(jamo) => {
+                let mut chars = jamo.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => kind.index_of(c),
+                    _ => None,
+                }
+                .with_context(|| format!("{}: \"{}\" の字母 \"{}\" は不正です", kind.name(), key, jamo))?
+            }
+        };
+        entries.push((key, index));
+    }
+    entries.sort();
+
+    if definition.mode == TableMode::Replace {
+        table.clear();
+    }
+    for (key, index) in entries {
+        if let Some(base) = table.insert(key.clone(), index) {
+            if base != index {
+                conflicts.push(TableConflict::Override { kind, key, base, custom: index });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 記号テーブル定義を検証して適用する。
+///
+/// キーは英字で始まらない空白・大文字を含まない綴り、値は空でない文字列。
+fn apply_symbol_table(
+    definition: SymbolTableJson,
+    table: &mut HashMap<String, String>,
+    conflicts: &mut Vec<TableConflict>,
+) -> Result<()> {
+    let mut entries = Vec::with_capacity(definition.entries.len());
+    for (key, value) in definition.entries {
+        let starts_with_letter = key.chars().next().is_none_or(char::is_alphabetic);
+        if starts_with_letter || key.chars().any(|c| c.is_whitespace() || c.is_uppercase()) {
+            bail!("symbols: 不正なキー \"{}\" (英字で始まるキー・空白・大文字は使用できません)", key);
+        }
+        if value.is_empty() {
+            bail!("symbols: \"{}\" の値が空です", key);
+        }
+        entries.push((key, value));
+    }
+    entries.sort();
+
+    if definition.mode == TableMode::Replace {
+        table.clear();
+    }
+    for (key, value) in entries {
+        if let Some(base) = table.insert(key.clone(), value.clone()) {
+            if base != value {
+                conflicts.push(TableConflict::SymbolOverride { key, base, custom: value });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 記号の綴り (綴り, 記号)。全角形は [`build_symbols`] で `\` + ASCII記号から生成する。
+pub const SYMBOLS: [(&str, &str); 13] = [
+    ("{{", "『"), ("}}", "』"), ("[[", "「"), ("]]", "」"),
+    ("<<", "《"), (">>", "》"), ("[<", "〈"), (">]", "〉"),
+    ("..", "·"), ("...", "…"), ("**", "※"), ("~~", "～"),
+    ("\"\"", "〃"),
+];
+
+/// 古語の初声の綴り (ローマ字, 結合型字母)。
+pub const ARCHAIC_CHOSEONG: [(&str, char); 19] = [
+    ("z", '\u{1140}'), ("x", '\u{114C}'), ("q", '\u{1159}'), ("v", '\u{112B}'),
+    ("f", '\u{1157}'), ("hh", '\u{1158}'), ("bg", '\u{111E}'), ("bd", '\u{1120}'),
+    ("bs", '\u{1121}'), ("bsg", '\u{1122}'), ("bsd", '\u{1123}'), ("bj", '\u{1127}'),
+    ("bt", '\u{1129}'), ("sg", '\u{112D}'), ("sn", '\u{112E}'), ("sd", '\u{112F}'),
+    ("sb", '\u{1132}'), ("sj", '\u{1136}'), ("ld", '\u{A966}'),
+];
+
+/// 古語の中声の綴り (ローマ字, 結合型字母)。
+pub const ARCHAIC_JUNGSEONG: [(&str, char); 10] = [
+    ("aa", '\u{119E}'), ("aai", '\u{11A1}'), ("aaeo", '\u{119F}'), ("yoya", '\u{1184}'),
+    ("yoyae", '\u{1185}'), ("yoi", '\u{1188}'), ("yuyeo", '\u{1191}'), ("yuye", '\u{1192}'),
+    ("yui", '\u{1194}'), ("oyeo", '\u{D7B0}'),
+];
+
+/// 古語の終声の綴り (ローマ字, 結合型字母)。
+pub const ARCHAIC_JONGSEONG: [(&str, char); 11] = [
+    ("z", '\u{11EB}'), ("x", '\u{11F0}'), ("q", '\u{11F9}'), ("v", '\u{11E6}'),
+    ("f", '\u{11F4}'), ("lgs", '\u{11CC}'), ("lz", '\u{11D7}'), ("lq", '\u{11D9}'),
+    ("ms", '\u{11DD}'), ("xs", '\u{11F1}'), ("bd", '\u{D7E3}'),
+];
+
+/// 記号テーブルを構築する。
+///
+/// [`SYMBOLS`] に加え、`\` + ASCII の記号・数字を全角形 (`\!` → ！, `\1` → １) に割り当てる。
+fn build_symbols() -> HashMap<String, String> {
+    let fullwidth = ('!'..='~')
+        .filter(|c| !c.is_ascii_alphabetic())
+        .filter_map(|c| Some((format!("\\{}", c), char::from_u32(c as u32 + 0xFEE0)?.to_string())));
+    SYMBOLS
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .chain(fullwidth)
+        .collect()
+}
+
+/// 初声マッピングを構築する。
+fn build_choseong() -> HashMap<String, u32> {
+    [
+        ("g", 0), ("gg", 1), ("kk", 1), ("n", 2), ("d", 3), ("dd", 4), ("tt", 4),
+        ("r", 5), ("l", 5), ("m", 6), ("b", 7), ("bb", 8), ("pp", 8),
+        ("s", 9), ("ss", 10), ("j", 12), ("jj", 13),
+        ("ch", 14), ("k", 15), ("t", 16), ("p", 17), ("h", 18),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// 中声マッピングを構築する。
+fn build_jungseong() -> HashMap<String, u32> {
+    [
+        ("a", 0), ("ae", 1), ("ya", 2), ("yae", 3), ("eo", 4),
+        ("e", 5), ("yeo", 6), ("ye", 7), ("o", 8), ("wa", 9),
+        ("wae", 10), ("oe", 11), ("yo", 12), ("u", 13), ("wo", 14),
+        ("we", 15), ("wi", 16), ("yu", 17), ("eu", 18), ("ui", 19),
+        ("i", 20),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// 終声マッピングを構築する。
+fn build_jongseong() -> HashMap<String, u32> {
+    [
+        ("g", 1), ("gg", 2), ("gs", 3), ("n", 4),
+        ("nj", 5), ("nh", 6), ("d", 7), ("l", 8), ("lg", 9),
+        ("lm", 10), ("lb", 11), ("ls", 12), ("lt", 13), ("lp", 14),
+        ("lh", 15), ("m", 16), ("b", 17), ("bs", 18), ("s", 19),
+        ("ss", 20), ("ng", 21), ("j", 22), ("ch", 23), ("k", 24),
+        ("t", 25), ("p", 26), ("h", 27),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// McCune–Reischauer 式の初声マッピングを構築する。
+fn build_mr_choseong() -> HashMap<String, u32> {
+    [
+        ("k", 0), ("g", 0), ("kk", 1), ("n", 2), ("t", 3), ("d", 3), ("tt", 4),
+        ("r", 5), ("l", 5), ("m", 6), ("p", 7), ("b", 7), ("pp", 8),
+        ("s", 9), ("ss", 10), ("ch", 12), ("j", 12), ("tch", 13),
+        ("ch'", 14), ("k'", 15), ("t'", 16), ("p'", 17), ("h", 18),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// McCune–Reischauer 式の中声マッピングを構築する。
+///
+/// ŏ・ŭ は合成済み文字・結合文字 (U+0306)・ASCII 代替 (^) のいずれでも入力できる。
+fn build_mr_jungseong() -> HashMap<String, u32> {
+    let base = [
+        ("a", 0), ("ae", 1), ("ya", 2), ("yae", 3), ("ŏ", 4),
+        ("e", 5), ("yŏ", 6), ("ye", 7), ("o", 8), ("wa", 9),
+        ("wae", 10), ("oe", 11), ("yo", 12), ("u", 13), ("wŏ", 14),
+        ("we", 15), ("wi", 16), ("yu", 17), ("ŭ", 18), ("ŭi", 19),
+        ("i", 20),
+    ];
+    let mut map = HashMap::new();
+    for (key, index) in base {
+        map.insert(key.to_string(), index);
+        if key.contains(['ŏ', 'ŭ']) {
+            for (o, u) in [("o\u{306}", "u\u{306}"), ("o^", "u^"), ("ǒ", "ǔ")] {
+                map.insert(key.replace('ŏ', o).replace('ŭ', u), index);
+            }
+        }
+    }
+    map
+}
+
+/// McCune–Reischauer 式の終声マッピングを構築する。
+fn build_mr_jongseong() -> HashMap<String, u32> {
+    [
+        ("k", 1), ("g", 1), ("kk", 2), ("ks", 3), ("n", 4),
+        ("nch", 5), ("nj", 5), ("nh", 6), ("t", 7), ("d", 7), ("l", 8), ("lk", 9),
+        ("lm", 10), ("lp", 11), ("ls", 12), ("lt'", 13), ("lp'", 14),
+        ("lh", 15), ("m", 16), ("p", 17), ("b", 17), ("ps", 18), ("s", 19),
+        ("ss", 20), ("ng", 21), ("ch", 22), ("j", 22), ("ch'", 23), ("k'", 24),
+        ("t'", 25), ("p'", 26), ("h", 27),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// Yale 式の初声マッピングを構築する。
+fn build_yale_choseong() -> HashMap<String, u32> {
+    [
+        ("k", 0), ("kk", 1), ("n", 2), ("t", 3), ("tt", 4), ("l", 5),
+        ("m", 6), ("p", 7), ("pp", 8), ("s", 9), ("ss", 10), ("c", 12),
+        ("cc", 13), ("ch", 14), ("kh", 15), ("th", 16), ("ph", 17), ("h", 18),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// Yale 式の中声マッピングを構築する。
+fn build_yale_jungseong() -> HashMap<String, u32> {
+    [
+        ("a", 0), ("ay", 1), ("ya", 2), ("yay", 3), ("e", 4),
+        ("ey", 5), ("ye", 6), ("yey", 7), ("o", 8), ("wa", 9),
+        ("way", 10), ("oy", 11), ("yo", 12), ("wu", 13), ("we", 14),
+        ("wey", 15), ("wi", 16), ("yu", 17), ("u", 18), ("uy", 19),
+        ("i", 20),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+/// Yale 式の終声マッピングを構築する。
+fn build_yale_jongseong() -> HashMap<String, u32> {
+    [
+        ("k", 1), ("kk", 2), ("ks", 3), ("n", 4), ("nc", 5), ("nh", 6),
+        ("t", 7), ("l", 8), ("lk", 9), ("lm", 10), ("lp", 11), ("ls", 12),
+        ("lth", 13), ("lph", 14), ("lh", 15), ("m", 16), ("p", 17), ("ps", 18),
+        ("s", 19), ("ss", 20), ("ng", 21), ("c", 22), ("ch", 23), ("kh", 24),
+        ("th", 25), ("ph", 26), ("h", 27),
+    ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_extend_tables() {
+        let json = r#"{
+            "choseong": { "entries": { "q": "ㄲ" } },
+            "jungseong": { "entries": { "oo": "ㅜ", "ee": 20 } }
+        }"#;
+        let (tables, conflicts) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(tables.choseong.get("q"), Some(&1));
+        assert_eq!(tables.choseong.get("g"), Some(&0));
+        assert_eq!(tables.jungseong.get("oo"), Some(&13));
+        assert_eq!(tables.jungseong.get("ee"), Some(&20));
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn test_replace_table() {
+        let json = r#"{ "jongseong": { "mode": "replace", "entries": { "k": "ㄱ", "n": "ㄴ" } } }"#;
+        let (tables, _) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(tables.jongseong.len(), 2);
+        assert_eq!(tables.jongseong.get("k"), Some(&1));
+        // 他のテーブルは標準のまま。
+        assert_eq!(tables.choseong, RomajaTables::standard().choseong);
+    }
+
+    #[test]
+    fn test_override_conflict() {
+        let json = r#"{ "choseong": { "entries": { "k": "ㄱ", "g": "ㄱ" } } }"#;
+        let (tables, conflicts) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(tables.choseong.get("k"), Some(&0));
+        // 同じ字母での再定義は競合にならない。
+        assert_eq!(
+            conflicts,
+            vec![TableConflict::Override {
+                kind: JamoKind::Choseong,
+                key: "k".to_string(),
+                base: 15,
+                custom: 0,
+            }]
+        );
+        assert_eq!(conflicts[0].to_string(), "choseong: \"k\" は既定の ㅋ を ㄱ で上書きします");
+    }
+
+    #[test]
+    fn test_ambiguous_conflict() {
+        let json = r#"{ "choseong": { "entries": { "w": "ㅇ" } }, "jungseong": { "entries": { "w": "ㅜ" } } }"#;
+        let (_, conflicts) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(conflicts, vec![TableConflict::Ambiguous { key: "w".to_string() }]);
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        // インデックスの範囲外。
+        let err = RomajaTables::from_json(r#"{ "choseong": { "entries": { "q": 19 } } }"#).unwrap_err();
+        assert!(err.to_string().contains("範囲外"));
+        // 終声インデックス0 (終声なし) は不可。
+        assert!(RomajaTables::from_json(r#"{ "jongseong": { "entries": { "q": 0 } } }"#).is_err());
+        // 位置に合わない字母 (ㄸ は終声にならない)。
+        let err = RomajaTables::from_json(r#"{ "jongseong": { "entries": { "q": "ㄸ" } } }"#).unwrap_err();
+        assert!(err.to_string().contains("ㄸ"));
+        // 母音に子音。
+        assert!(RomajaTables::from_json(r#"{ "jungseong": { "entries": { "q": "ㄱ" } } }"#).is_err());
+        // 不正なキー。
+        assert!(RomajaTables::from_json(r#"{ "choseong": { "entries": { "Q": "ㄲ" } } }"#).is_err());
+        assert!(RomajaTables::from_json(r#"{ "choseong": { "entries": { "a b": "ㄲ" } } }"#).is_err());
+        assert!(RomajaTables::from_json(r#"{ "choseong": { "entries": { "": "ㄲ" } } }"#).is_err());
+        // 不正なJSON・モード。
+        assert!(RomajaTables::from_json("not json").is_err());
+        assert!(RomajaTables::from_json(r#"{ "choseong": { "mode": "merge" } }"#).is_err());
+    }
+
+    #[test]
+    fn test_with_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("table.json");
+        fs::write(&path, r#"{ "choseong": { "entries": { "q": "ㄲ" } } }"#).unwrap();
+        assert_eq!(RomajaTables::standard().with_file(&path).choseong.get("q"), Some(&1));
+
+        // 元のテーブルを拡張する。
+        let tables = RomajaTables::mccune_reischauer().with_file(&path);
+        assert_eq!(tables.choseong.get("q"), Some(&1));
+        assert_eq!(tables.choseong.get("k'"), Some(&15));
+
+        fs::write(&path, r#"{ "choseong": { "entries": { "q": 99 } } }"#).unwrap();
+        assert_eq!(RomajaTables::standard().with_file(&path), RomajaTables::standard());
+
+        let missing = dir.path().join("missing.json");
+        assert_eq!(RomajaTables::standard().with_file(&missing), RomajaTables::standard());
+    }
+
+    #[test]
+    fn test_mccune_reischauer_tables() {
+        let tables = RomajaTables::mccune_reischauer();
+        for key in ["ŏ", "o\u{306}", "o^", "ǒ"] {
+            assert_eq!(tables.jungseong.get(key), Some(&4), "key: {:?}", key);
+        }
+        for key in ["yŏ", "yo^", "wŏ", "wo^", "ŭi", "u^i"] {
+            assert!(tables.jungseong.contains_key(key), "key: {:?}", key);
+        }
+        assert_eq!(tables.jungseong.get("o"), Some(&8));
+        assert_eq!(tables.separators, vec!['\'']);
+    }
+
+    #[test]
+    fn test_yale_tables() {
+        let tables = RomajaTables::yale();
+        assert_eq!(tables.jungseong.get("wu"), Some(&13));
+        assert_eq!(tables.jungseong.get("u"), Some(&18));
+        assert_eq!(tables.labial_jungseong.get("u"), Some(&13));
+        assert_eq!(tables.choseong.get("c"), Some(&12));
+        assert_eq!(tables.jongseong.len(), 27);
+    }
+
+    #[test]
+    fn test_symbol_table() {
+        let tables = RomajaTables::standard();
+        assert_eq!(tables.symbols.get("{{").map(String::as_str), Some("『"));
+        assert_eq!(tables.symbols.get("\\!").map(String::as_str), Some("！"));
+        assert_eq!(tables.symbols.get("\\1").map(String::as_str), Some("１"));
+        assert_eq!(tables.symbols.get("\\~").map(String::as_str), Some("～"));
+        assert_eq!(tables.symbols.get("\\a"), None);
+        assert_eq!(RomajaTables::yale().symbols, tables.symbols);
+
+        let json = r#"{ "symbols": { "entries": { "->": "→", "**": "＊" } } }"#;
+        let (tables, conflicts) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(tables.symbols.get("->").map(String::as_str), Some("→"));
+        assert_eq!(tables.symbols.get("[[").map(String::as_str), Some("「"));
+        assert_eq!(
+            conflicts,
+            vec![TableConflict::SymbolOverride {
+                key: "**".to_string(),
+                base: "※".to_string(),
+                custom: "＊".to_string(),
+            }]
+        );
+
+        let json = r#"{ "symbols": { "mode": "replace", "entries": { "->": "→" } } }"#;
+        let (tables, _) = RomajaTables::from_json(json).unwrap();
+        assert_eq!(tables.symbols.len(), 1);
+
+        // 英字で始まるキー・空白・大文字・空の値は不正。
+        for entries in [r#"{ "a.": "x" }"#, r#"{ ". .": "x" }"#, r#"{ ".A": "x" }"#, r#"{ "": "x" }"#, r#"{ "..": "" }"#] {
+            let json = format!(r#"{{ "symbols": {{ "entries": {} }} }}"#, entries);
+            assert!(RomajaTables::from_json(&json).is_err(), "entries: {}", entries);
+        }
+    }
+
+    #[test]
+    fn test_with_shift_variants() {
+        let tables = RomajaTables::standard().with_shift_variants();
+        assert_eq!(tables.choseong.get("G"), Some(&1));
+        assert_eq!(tables.choseong.get("J"), Some(&13));
+        assert_eq!(tables.jongseong.get("S"), Some(&20));
+        assert_eq!(tables.jungseong.get("A"), Some(&2));
+        assert_eq!(tables.jungseong.get("Ae"), Some(&3));
+        assert_eq!(tables.jungseong.get("Eo"), Some(&6));
+        assert_eq!(tables.jungseong.get("E"), Some(&7));
+        assert_eq!(tables.choseong.get("K"), Some(&1));
+        // 2ボル式のキー位置ではなく綴りに従う (O → ㅛ, P → ㅃ)。
+        assert_eq!(tables.jungseong.get("O"), Some(&12));
+        assert_eq!(tables.choseong.get("P"), Some(&8));
+        // 対応するキーがない綴りは追加しない。
+        assert_eq!(tables.choseong.get("N"), None);
+        assert_eq!(tables.jungseong.get("I"), None);
+
+        // 入力方式ごとのテーブルから作る (Yale の濃音は kk, ㅈ は c)。
+        let yale = RomajaTables::yale().with_shift_variants();
+        assert_eq!(yale.choseong.get("K"), Some(&1));
+        assert_eq!(yale.choseong.get("C"), Some(&13));
+    }
+
+    #[test]
+    fn test_with_archaic() {
+        let tables = RomajaTables::standard().with_archaic();
+        assert_eq!(tables.choseong.get("z"), Some(&19));
+        assert_eq!(tables.jungseong.get("aa"), Some(&21));
+        assert_eq!(tables.jongseong.get("x"), Some(&29));
+        // 既存のキーは上書きしない。
+        assert_eq!(tables.choseong.get("g"), Some(&0));
+        assert_eq!(
+            tables.choseong.len(),
+            RomajaTables::standard().choseong.len() + ARCHAIC_CHOSEONG.len()
+        );
+    }
+}
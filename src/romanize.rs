@@ -0,0 +1,408 @@
+//! ハングル→ローマ字 (国語のローマ字表記法) 逆変換モジュール。
+//!
+//! 完成形ハングル (U+AC00〜U+D7A3) を初声・中声・終声に分解し、
+//! 文化観光部告示の「国語のローマ字表記法」(Revised Romanization) に従って
+//! ローマ字に変換する。
+//!
+//! - 標準表記: 発音どおりに表記する (連音化・鼻音化・流音化・激音化・口蓋音化)
+//! - 転写表記 (`strict`): 綴りどおり字母単位で表記する (ㄱ/ㄷ/ㅂ/ㄹ は常に g/d/b/l)
+//!
+//! ハングル以外の文字はそのまま出力する。
+
+use crate::jamo::{self, Jong};
+
+/// 初声 ㅇ のインデックス。
+const CHO_IEUNG: u32 = 11;
+/// 初声 ㅎ のインデックス。
+const CHO_HIEUH: u32 = 18;
+/// 中声 ㅣ のインデックス。
+const JUNG_I: u32 = 20;
+
+/// 初声のローマ字 (標準表記)。
+const CHOSEONG: [&str; 19] = [
+    "g", "kk", "n", "d", "tt", "r", "m", "b", "pp",
+    "s", "ss", "", "j", "jj", "ch", "k", "t", "p", "h",
+];
+
+/// 中声のローマ字。
+const JUNGSEONG: [&str; 21] = [
+    "a", "ae", "ya", "yae", "eo", "e", "yeo", "ye", "o", "wa",
+    "wae", "oe", "yo", "u", "wo", "we", "wi", "yu", "eu", "ui",
+    "i",
+];
+
+/// 終声の綴り (転写表記)。
+const JONGSEONG_STRICT: [&str; 28] = [
+    "", "g", "kk", "gs", "n", "nj", "nh", "d", "l", "lg",
+    "lm", "lb", "ls", "lt", "lp", "lh", "m", "b", "bs", "s",
+    "ss", "ng", "j", "ch", "k", "t", "p", "h",
+];
+
+/// 終声の代表音 (標準表記、子音の前または語末)。
+const JONGSEONG_CODA: [&str; 28] = [
+    "", "k", "k", "k", "n", "n", "n", "t", "l", "k",
+    "m", "l", "l", "l", "p", "l", "m", "p", "p", "t",
+    "t", "ng", "t", "t", "k", "t", "p", "t",
+];
+
+/// 終声が母音の前で分かれるときの (残る終声, 次の初声)。
+///
+/// 連音化で次の音節 (初声 ㅇ) に移る子音を表す。
+/// ㅎ は母音の前で脱落する。ㅇ は移動しない。
+const JONGSEONG_LIAISON: [(&str, &str); 28] = [
+    ("", ""), ("", "g"), ("", "kk"), ("k", "s"), ("", "n"),
+    ("n", "j"), ("", "n"), ("", "d"), ("", "r"), ("l", "g"),
+    ("l", "m"), ("l", "b"), ("l", "s"), ("l", "t"), ("l", "p"),
+    ("", "r"), ("", "m"), ("", "b"), ("p", "s"), ("", "s"),
+    ("", "ss"), ("ng", ""), ("", "j"), ("", "ch"), ("", "k"),
+    ("", "t"), ("", "p"), ("", ""),
+];
+
+/// 逆変換のオプション。
+#[derive(Debug, Clone, Copy)]
+pub struct RomanizeOptions {
+    /// 音節境界の音韻変化 (鼻音化・流音化・激音化・口蓋音化) を反映するか。
+    ///
+    /// 連音化と ㄹㄹ → ll は常に反映する。`strict` 時は無視される。
+    pub assimilation: bool,
+    /// 転写表記 (綴りどおり字母単位で変換する)。
+    ///
+    /// 無音の ㅇ と紛らわしい子音連続はハイフンで区切る。
+    pub strict: bool,
+}
+
+impl Default for RomanizeOptions {
+    fn default() -> Self {
+        Self {
+            assimilation: true,
+            strict: false,
+        }
+    }
+}
+
+/// 音節の字母インデックス (初声, 中声, 終声)。
+#[derive(Clone, Copy)]
+struct Syllable {
+    cho: u32,
+    jung: u32,
+    jong: u32,
+}
+
+/// 完成形ハングルを字母インデックスに分解する。
+fn decompose(c: char) -> Option<Syllable> {
+    let (cho, jung, jong) = jamo::decompose(c)?;
+    Some(Syllable {
+        cho: cho.index(),
+        jung: jung.index(),
+        jong: jong.map_or(0, Jong::index),
+    })
+}
+
+/// ハングル文字列をローマ字に変換する。
+///
+/// 連続するハングル音節を1語として扱い、音節境界の規則を適用する。
+pub fn romanize(input: &str, options: &RomanizeOptions) -> String {
+    let mut result = String::new();
+    let mut word: Vec<Syllable> = Vec::new();
+
+    for c in input.chars() {
+        match decompose(c) {
+            Some(s) => word.push(s),
+            None => {
+                flush_word(&mut word, options, &mut result);
+                result.push(c);
+            }
+        }
+    }
+    flush_word(&mut word, options, &mut result);
+
+    result
+}
+
+/// 溜まった音節列を1語として変換し、出力に追加する。
+fn flush_word(word: &mut Vec<Syllable>, options: &RomanizeOptions, out: &mut String) {
+    if word.is_empty() {
+        return;
+    }
+    if options.strict {
+        romanize_word_strict(word, out);
+    } else {
+        romanize_word(word, options.assimilation, out);
+    }
+    word.clear();
+}
+
+/// 1語を標準表記で変換する。
+fn romanize_word(word: &[Syllable], assimilation: bool, out: &mut String) {
+    let mut onset = CHOSEONG[word[0].cho as usize];
+
+    for (i, s) in word.iter().enumerate() {
+        out.push_str(onset);
+        out.push_str(JUNGSEONG[s.jung as usize]);
+
+        match word.get(i + 1) {
+            Some(next) => {
+                let (coda, next_onset) = resolve_boundary(s.jong, next, assimilation);
+                out.push_str(coda);
+                onset = next_onset;
+            }
+            None => out.push_str(JONGSEONG_CODA[s.jong as usize]),
+        }
+    }
+}
+
+/// 音節境界の (終声, 次の初声) を決定する。
+fn resolve_boundary(jong: u32, next: &Syllable, assimilation: bool) -> (&'static str, &'static str) {
+    let next_cho = CHOSEONG[next.cho as usize];
+
+    // 終声なし。
+    if jong == 0 {
+        return ("", next_cho);
+    }
+
+    // 連音化: 終声 + 無音の ㅇ。
+    if next.cho == CHO_IEUNG {
+        let (coda, moved) = JONGSEONG_LIAISON[jong as usize];
+        // 口蓋音化: ㄷ/ㅌ + 이 → 지/치。
+        if assimilation && next.jung == JUNG_I {
+            match moved {
+                "d" => return (coda, "j"),
+                "t" => return (coda, "ch"),
+                _ => {}
+            }
+        }
+        return (coda, moved);
+    }
+
+    let coda = JONGSEONG_CODA[jong as usize];
+
+    // ㄹㄹ は常に ll。
+    if coda == "l" && next_cho == "r" {
+        return ("l", "l");
+    }
+
+    if !assimilation {
+        return (coda, next_cho);
+    }
+
+    // 激音化: ㅎ (ㄶ, ㅀ) + ㄱ/ㄷ/ㅈ → ㅋ/ㅌ/ㅊ。
+    if matches!(jong, 6 | 15 | 27) {
+        let remain = match jong {
+            6 => "n",
+            15 => "l",
+            _ => "",
+        };
+        match next_cho {
+            "g" => return (remain, "k"),
+            "d" => return (remain, "t"),
+            "j" => return (remain, "ch"),
+            // ㅎ + ㄴ → ㄴㄴ。
+            "n" if jong == 27 => return ("n", "n"),
+            _ => {}
+        }
+    }
+
+    // 激音化: ㄱ/ㄷ/ㅂ/ㅈ + ㅎ → ㅋ/ㅌ/ㅍ/ㅊ。
+    if next.cho == CHO_HIEUH {
+        let (remain, aspirated) = match jong {
+            1 | 2 | 24 => ("", "k"),
+            9 => ("l", "k"),
+            7 | 19 | 20 | 25 => ("", "t"),
+            22 | 23 => ("", "ch"),
+            17 | 26 => ("", "p"),
+            11 => ("l", "p"),
+            _ => (coda, "h"),
+        };
+        // 口蓋音化: ㄷ + 히 → 치。
+        if aspirated == "t" && next.jung == JUNG_I {
+            return (remain, "ch");
+        }
+        return (remain, aspirated);
+    }
+
+    match next_cho {
+        // 鼻音化: 閉鎖音 + ㄴ/ㅁ。
+        "n" | "m" => match coda {
+            // 流音化: ㄹ + ㄴ → ll。
+            "l" if next_cho == "n" => ("l", "l"),
+            _ => (nasalize(coda), next_cho),
+        },
+        "r" => match coda {
+            // 流音化: ㄴ + ㄹ → ll。
+            "n" => ("l", "l"),
+            // ㅁ/ㅇ + ㄹ → ㄴ。
+            "m" | "ng" => (coda, "n"),
+            // 閉鎖音 + ㄹ → 鼻音 + ㄴ。
+            _ => (nasalize(coda), "n"),
+        },
+        _ => (coda, next_cho),
+    }
+}
+
+/// 閉鎖音の終声を対応する鼻音にする (k→ng, t→n, p→m)。
+fn nasalize(coda: &'static str) -> &'static str {
+    match coda {
+        "k" => "ng",
+        "t" => "n",
+        "p" => "m",
+        other => other,
+    }
+}
+
+/// 1語を転写表記で変換する。
+fn romanize_word_strict(word: &[Syllable], out: &mut String) {
+    let mut prev_coda = "";
+
+    for (i, s) in word.iter().enumerate() {
+        let onset = if s.cho == CHO_IEUNG { "" } else { strict_choseong(s.cho) };
+
+        if i > 0 {
+            let ambiguous = match (prev_coda.chars().last(), onset.chars().next()) {
+                (Some(a), Some(b)) if a == b => {
+                    let doubled = format!("{a}{a}");
+                    prev_coda.ends_with(&doubled) || onset.starts_with(&doubled)
+                }
+                _ => false,
+            };
+            // 無音の ㅇ、または同じ字の3連続はハイフンで区切る。
+            if (s.cho == CHO_IEUNG && !prev_coda.is_empty()) || ambiguous {
+                out.push('-');
+            }
+        }
+
+        out.push_str(onset);
+        out.push_str(JUNGSEONG[s.jung as usize]);
+        prev_coda = JONGSEONG_STRICT[s.jong as usize];
+        out.push_str(prev_coda);
+    }
+}
+
+/// 転写表記の初声 (ㄹ は常に l)。
+fn strict_choseong(cho: u32) -> &'static str {
+    if cho == 5 {
+        "l"
+    } else {
+        CHOSEONG[cho as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rr(input: &str) -> String {
+        romanize(input, &RomanizeOptions::default())
+    }
+
+    fn strict(input: &str) -> String {
+        romanize(input, &RomanizeOptions { assimilation: true, strict: true })
+    }
+
+    #[test]
+    fn test_basic_syllables() {
+        assert_eq!(rr("가"), "ga");
+        assert_eq!(rr("한국"), "hanguk");
+        assert_eq!(rr("서울"), "seoul");
+        assert_eq!(rr("부산"), "busan");
+        assert_eq!(rr("라면"), "ramyeon");
+    }
+
+    #[test]
+    fn test_representative_finals() {
+        assert_eq!(rr("옥천"), "okcheon");
+        assert_eq!(rr("합덕"), "hapdeok");
+        assert_eq!(rr("월곶"), "wolgot");
+        assert_eq!(rr("벚꽃"), "beotkkot");
+        assert_eq!(rr("한밭"), "hanbat");
+        assert_eq!(rr("닭"), "dak");
+        assert_eq!(rr("삶"), "sam");
+    }
+
+    #[test]
+    fn test_liaison() {
+        assert_eq!(rr("한국어"), "hangugeo");
+        assert_eq!(rr("백암"), "baegam");
+        assert_eq!(rr("설악"), "seorak");
+        assert_eq!(rr("닭이"), "dalgi");
+        assert_eq!(rr("없어"), "eopseo");
+        assert_eq!(rr("좋아"), "joa");
+        assert_eq!(rr("않아"), "ana");
+        assert_eq!(rr("영어"), "yeongeo");
+    }
+
+    #[test]
+    fn test_lateral() {
+        assert_eq!(rr("울릉"), "ulleung");
+        assert_eq!(rr("신라"), "silla");
+        assert_eq!(rr("별내"), "byeollae");
+        assert_eq!(rr("대관령"), "daegwallyeong");
+    }
+
+    #[test]
+    fn test_nasalization() {
+        assert_eq!(rr("백마"), "baengma");
+        assert_eq!(rr("종로"), "jongno");
+        assert_eq!(rr("왕십리"), "wangsimni");
+        assert_eq!(rr("감사합니다"), "gamsahamnida");
+        assert_eq!(rr("받는"), "banneun");
+    }
+
+    #[test]
+    fn test_aspiration_and_palatalization() {
+        assert_eq!(rr("좋고"), "joko");
+        assert_eq!(rr("놓다"), "nota");
+        assert_eq!(rr("잡혀"), "japyeo");
+        assert_eq!(rr("낳지"), "nachi");
+        assert_eq!(rr("해돋이"), "haedoji");
+        assert_eq!(rr("같이"), "gachi");
+        assert_eq!(rr("굳히다"), "guchida");
+    }
+
+    #[test]
+    fn test_without_assimilation() {
+        let opts = RomanizeOptions { assimilation: false, strict: false };
+        assert_eq!(romanize("백마", &opts), "baekma");
+        assert_eq!(romanize("같이", &opts), "gati");
+        assert_eq!(romanize("좋고", &opts), "jotgo");
+        // 連音化と ㄹㄹ は常に反映。
+        assert_eq!(romanize("한국어", &opts), "hangugeo");
+        assert_eq!(romanize("울릉", &opts), "ulleung");
+    }
+
+    #[test]
+    fn test_strict_transliteration() {
+        assert_eq!(strict("집"), "jib");
+        assert_eq!(strict("짚"), "jip");
+        assert_eq!(strict("밖"), "bakk");
+        assert_eq!(strict("값"), "gabs");
+        assert_eq!(strict("붓꽃"), "buskkoch");
+        assert_eq!(strict("먹는"), "meogneun");
+        assert_eq!(strict("독립"), "doglib");
+        assert_eq!(strict("문리"), "munli");
+        assert_eq!(strict("물엿"), "mul-yeos");
+        assert_eq!(strict("굳이"), "gud-i");
+        assert_eq!(strict("좋다"), "johda");
+        assert_eq!(strict("가곡"), "gagog");
+        assert_eq!(strict("조랑말"), "jolangmal");
+        assert_eq!(strict("없었습니다"), "eobs-eoss-seubnida");
+    }
+
+    #[test]
+    fn test_passthrough() {
+        assert_eq!(rr(""), "");
+        assert_eq!(rr("abc 123"), "abc 123");
+        assert_eq!(rr("안녕하세요!"), "annyeonghaseyo!");
+        assert_eq!(rr("서울 부산"), "seoul busan");
+        // 互換字母は変換しない。
+        assert_eq!(rr("ㅋㅋ"), "ㅋㅋ");
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let c = crate::hangul::HangulConverter::new();
+        // 変換結果をローマ字に戻す (発音表記)。
+        assert_eq!(rr(&c.convert("han gug eo")), "hangugeo");
+        assert_eq!(rr(&c.convert("gam sa hab ni da")), "gamsahamnida");
+        assert_eq!(rr(&c.convert("an nyeong  ha se yo")), "annyeong haseyo");
+    }
+}
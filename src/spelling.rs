@@ -0,0 +1,402 @@
+//! 発音どおりの綴りから標準の綴りを推定するモジュール。
+//!
+//! 聞こえたとおりに入力した綴り (gamsahamnida → 감사함니다) を、単語リストを使って
+//! 標準の綴り (감사합니다) に戻す。単語リストの各語の発音を標準発音法の規則
+//! (連音化・鼻音化・流音化・激音化・口蓋音化・濃音化・終声の代表音化) で求め、
+//! 入力の発音と一致する語を候補とする。濃音・激音の前の同じ調音位置の終声
+//! (먹꼬 の ㄱ) は聞き分けにくいため、一致の判定では無視する (meokko → 머꼬 → 먹고)。
+//!
+//! 単語リストは1行1語のテキスト (`#` で始まる行と空行は無視)。
+//! 標準の単語リスト ([`SpellingDict::standard`]) に、ファイルやユーザー辞書の語を追加できる。
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::hangul::HangulConverter;
+use crate::jamo::{self, Jong};
+use crate::user_dict::UserDict;
+
+/// 標準の単語リスト。
+const STANDARD_WORDS: &str = include_str!("data/spelling_words.txt");
+
+/// 1語から組み合わせる候補の上限。
+const MAX_COMBINATIONS: usize = 8;
+
+/// 初声 ㅇ のインデックス。
+const CHO_IEUNG: u32 = 11;
+/// 初声 ㅎ のインデックス。
+const CHO_HIEUH: u32 = 18;
+/// 中声 ㅣ のインデックス。
+const JUNG_I: u32 = 20;
+
+/// 終声が母音の前で分かれるときの (残る終声, 次の初声) (終声のインデックス順)。
+///
+/// ㅎ は母音の前で脱落する。ㅇ は移動しない。
+const LIAISON: [(u32, u32); 28] = [
+    (0, 11), (0, 0), (0, 1), (1, 10), (0, 2), (4, 12), (0, 2), (0, 3), (0, 5), (8, 0),
+    (8, 6), (8, 7), (8, 10), (8, 16), (8, 17), (0, 5), (0, 6), (0, 7), (17, 10), (0, 9),
+    (0, 10), (21, 11), (0, 12), (0, 14), (0, 15), (0, 16), (0, 17), (0, 11),
+];
+
+/// 音節の字母インデックス (初声, 中声, 終声)。
+type Syllable = (u32, u32, u32);
+
+/// 発音が同じ語の辞書 (発音 → 標準の綴り)。
+pub struct SpellingDict {
+    /// 発音の照合キー → 標準の綴り (追加順)。
+    words: HashMap<String, Vec<String>>,
+}
+
+impl SpellingDict {
+    /// 空の辞書を作成する。
+    pub fn empty() -> Self {
+        Self { words: HashMap::new() }
+    }
+
+    /// 標準の単語リスト (あいさつ・学習者向けの基本語) の辞書を作成する。
+    pub fn standard() -> Self {
+        let mut dict = Self::empty();
+        dict.extend_words(STANDARD_WORDS);
+        dict
+    }
+
+    /// 語を追加する。ハングル以外の文字を含む語は無視する。
+    pub fn add_word(&mut self, word: &str) {
+        if word.is_empty() || !word.chars().all(jamo::is_syllable) {
+            return;
+        }
+        let spellings = self.words.entry(sound_key(word)).or_default();
+        if !spellings.iter().any(|w| w == word) {
+            spellings.push(word.to_string());
+        }
+    }
+
+    /// 単語リストのテキスト (1行1語、`#` で始まる行と空行は無視) の語を追加する。
+    pub fn extend_words(&mut self, text: &str) {
+        for line in text.lines().map(str::trim) {
+            if !line.starts_with('#') {
+                self.add_word(line);
+            }
+        }
+    }
+
+    /// 単語リストファイルの語を追加する。
+    pub fn extend_file(&mut self, path: &Path) -> Result<()> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("単語リストを読み込めません: {}", path.display()))?;
+        self.extend_words(&content);
+        Ok(())
+    }
+
+    /// ユーザー辞書の値に含まれる語 (空白区切り) を追加する。
+    pub fn extend_user_dict(&mut self, dict: &UserDict) {
+        for value in dict.values() {
+            for word in value.split_whitespace() {
+                self.add_word(word);
+            }
+        }
+    }
+
+    /// ハングル文字列と発音が同じで綴りの異なる候補を返す (最大 [`MAX_COMBINATIONS`] 件)。
+    ///
+    /// スペースで区切った語ごとに辞書を引き、一致した語を標準の綴りに置き換える。
+    pub fn lookup(&self, text: &str) -> Vec<String> {
+        let mut results = vec![String::new()];
+        for (i, token) in text.split(' ').enumerate() {
+            let mut options: Vec<&str> = self
+                .words
+                .get(&sound_key(token))
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|&w| w != token)
+                .collect();
+            options.push(token);
+            results = results
+                .iter()
+                .flat_map(|r| {
+                    let separator = if i > 0 { " " } else { "" };
+                    options.iter().map(move |o| format!("{}{}{}", r, separator, o))
+                })
+                .take(MAX_COMBINATIONS + 1)
+                .collect();
+        }
+        results.retain(|r| r != text);
+        results.truncate(MAX_COMBINATIONS);
+        results
+    }
+
+    /// ローマ字の入力を変換し、標準の綴りの候補を最大 `limit` 件返す。
+    ///
+    /// 音節の区切り方が異なる変換候補 ([`HangulConverter::convert_candidates`]) を
+    /// 最大 [`MAX_COMBINATIONS`] 件まで、スコアの高い順に辞書を引く。
+    /// 変換結果と同じ綴りは含まない。
+    pub fn candidates(&self, converter: &HangulConverter, input: &str, limit: usize) -> Vec<String> {
+        let mut results: Vec<String> = Vec::new();
+        for candidate in converter.convert_candidates(input, MAX_COMBINATIONS) {
+            for spelling in self.lookup(&candidate.text) {
+                if !results.contains(&spelling) {
+                    results.push(spelling);
+                }
+            }
+        }
+        results.truncate(limit);
+        results
+    }
+}
+
+impl Default for SpellingDict {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+/// ハングル文字列の発音をハングルで返す (감사합니다 → 감사함니다, 같이 → 가치)。
+///
+/// 連続するハングル音節を1語として音節境界の規則を適用し、語末の終声は代表音にする。
+/// 例外的な発音 (밟다 → 밥따 など) は扱わない。ハングル以外の文字はそのまま出力する。
+pub fn pronounce(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut word: Vec<Syllable> = Vec::new();
+    for c in text.chars() {
+        match jamo::decompose(c) {
+            Some((cho, jung, jong)) => word.push((cho.index(), jung.index(), jong.map_or(0, Jong::index))),
+            None => {
+                flush_word(&mut word, &mut out);
+                out.push(c);
+            }
+        }
+    }
+    flush_word(&mut word, &mut out);
+    out
+}
+
+/// 発音の照合キーを返す。
+///
+/// 発音から濃音・激音の前の同じ調音位置の終声 (ㄱ + ㄲ/ㅋ, ㄷ + ㄸ/ㅆ/ㅉ/ㅌ/ㅊ, ㅂ + ㅃ/ㅍ) を除く。
+fn sound_key(text: &str) -> String {
+    let syllables: Vec<char> = pronounce(text).chars().collect();
+    let mut out = String::with_capacity(text.len());
+    for (i, &c) in syllables.iter().enumerate() {
+        let next_cho = syllables.get(i + 1).and_then(|&n| jamo::decompose(n)).map(|(cho, _, _)| cho.index());
+        match jamo::decompose(c) {
+            Some((cho, jung, Some(jong))) if is_geminate(jong.index(), next_cho) => {
+                jamo::push_syllable(cho.index(), jung.index(), 0, &mut out);
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// 終声が次の濃音・激音の初声と同じ調音位置か判定する。
+fn is_geminate(jong: u32, next_cho: Option<u32>) -> bool {
+    matches!(
+        (jong, next_cho),
+        (1, Some(1 | 15)) | (7, Some(4 | 10 | 13 | 14 | 16)) | (17, Some(8 | 17))
+    )
+}
+
+/// 溜まった音節列に発音の規則を適用して出力に追加する。
+fn flush_word(word: &mut Vec<Syllable>, out: &mut String) {
+    for i in 0..word.len() {
+        let jong = match word.get(i + 1) {
+            Some(&(cho, jung, _)) => {
+                let (jong, next_cho) = resolve_boundary(word[i].2, cho, jung);
+                word[i + 1].0 = next_cho;
+                jong
+            }
+            None => representative(word[i].2),
+        };
+        let (cho, jung, _) = word[i];
+        jamo::push_syllable(cho, jung, jong, out);
+    }
+    word.clear();
+}
+
+/// 音節境界の (終声, 次の初声) を決定する。
+fn resolve_boundary(jong: u32, cho: u32, jung: u32) -> (u32, u32) {
+    if jong == 0 {
+        return (0, cho);
+    }
+
+    // 連音化 (口蓋音化: ㄷ/ㅌ + 이 → 지/치)。
+    if cho == CHO_IEUNG {
+        let (jong, moved) = LIAISON[jong as usize];
+        return match moved {
+            3 if jung == JUNG_I => (jong, 12),
+            16 if jung == JUNG_I => (jong, 14),
+            _ => (jong, moved),
+        };
+    }
+
+    // 激音化: ㄱ/ㄷ/ㅂ/ㅈ + ㅎ → ㅋ/ㅌ/ㅍ/ㅊ (ㄷ + 히 → 치)。
+    if cho == CHO_HIEUH {
+        return match jong {
+            1 | 2 | 3 | 24 => (0, 15),
+            9 => (8, 15),
+            7 | 19 | 20 | 25 if jung == JUNG_I => (0, 14),
+            7 | 19 | 20 | 25 => (0, 16),
+            22 | 23 => (0, 14),
+            5 => (4, 14),
+            17 | 18 | 26 => (0, 17),
+            11 | 14 => (8, 17),
+            _ => (representative(jong), cho),
+        };
+    }
+
+    // ㅎ (ㄶ, ㅀ) + ㄱ/ㄷ/ㅈ → ㅋ/ㅌ/ㅊ, + ㅅ → ㅆ, + ㄴ → ㄴㄴ (ㅀ は ㄹㄹ)。
+    if matches!(jong, 6 | 15 | 27) {
+        let remain = match jong {
+            6 => 4,
+            15 => 8,
+            _ => 0,
+        };
+        match cho {
+            0 => return (remain, 15),
+            3 => return (remain, 16),
+            12 => return (remain, 14),
+            9 => return (remain, 10),
+            2 if jong == 15 => return (8, 5),
+            2 => return (4, 2),
+            _ => {}
+        }
+    }
+
+    // ㄺ + ㄱ → ㄹㄲ (읽고 → 일꼬)。
+    if jong == 9 && cho == 0 {
+        return (8, 1);
+    }
+
+    let coda = representative(jong);
+    match (coda, cho) {
+        // 鼻音化: 閉鎖音 + ㄴ/ㅁ。
+        (1 | 7 | 17, 2 | 6) => (nasalize(coda), cho),
+        // 流音化: ㄴ + ㄹ, ㄹ + ㄴ → ㄹㄹ。
+        (4, 5) | (8, 2) => (8, 5),
+        // ㅁ/ㅇ + ㄹ → ㄴ、閉鎖音 + ㄹ → 鼻音 + ㄴ。
+        (16 | 21, 5) => (coda, 2),
+        (1 | 7 | 17, 5) => (nasalize(coda), 2),
+        // 濃音化: 閉鎖音の後、語幹末の ㄵ/ㄻ/ㄼ/ㄾ の後。
+        (1 | 7 | 17, 0 | 3 | 7 | 9 | 12) => (coda, tense(cho)),
+        (_, 0 | 3 | 9 | 12) if matches!(jong, 5 | 10 | 11 | 13) => (coda, tense(cho)),
+        _ => (coda, cho),
+    }
+}
+
+/// 終声の代表音 (子音の前・語末の発音) を返す。
+fn representative(jong: u32) -> u32 {
+    match jong {
+        // ㄲ ㄳ ㄺ ㅋ → ㄱ。
+        2 | 3 | 9 | 24 => 1,
+        // ㄵ ㄶ → ㄴ。
+        5 | 6 => 4,
+        // ㅅ ㅆ ㅈ ㅊ ㅌ ㅎ → ㄷ。
+        19 | 20 | 22 | 23 | 25 | 27 => 7,
+        // ㄼ ㄽ ㄾ ㅀ → ㄹ。
+        11 | 12 | 13 | 15 => 8,
+        // ㄻ → ㅁ。
+        10 => 16,
+        // ㄿ ㅄ ㅍ → ㅂ。
+        14 | 18 | 26 => 17,
+        other => other,
+    }
+}
+
+/// 閉鎖音の終声 (ㄱ, ㄷ, ㅂ) を対応する鼻音 (ㅇ, ㄴ, ㅁ) にする。
+fn nasalize(jong: u32) -> u32 {
+    match jong {
+        1 => 21,
+        7 => 4,
+        17 => 16,
+        other => other,
+    }
+}
+
+/// 平音の初声 (ㄱ, ㄷ, ㅂ, ㅅ, ㅈ) を濃音にする。
+fn tense(cho: u32) -> u32 {
+    match cho {
+        0 | 3 | 7 | 9 | 12 => cho + 1,
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pronounce() {
+        let cases = [
+            ("감사합니다", "감사함니다"),
+            ("한국어", "한구거"),
+            ("먹고", "먹꼬"),
+            ("같이", "가치"),
+            ("굳이", "구지"),
+            ("축하", "추카"),
+            ("좋다", "조타"),
+            ("놓는", "논는"),
+            ("괜찮아요", "괜차나요"),
+            ("없어요", "업써요"),
+            ("읽고", "일꼬"),
+            ("앉다", "안따"),
+            ("신라", "실라"),
+            ("설날", "설랄"),
+            ("독립", "동닙"),
+            ("대통령", "대통녕"),
+            ("부엌", "부억"),
+            ("맛있다", "마싣따"),
+            ("값이 싸요", "갑씨 싸요"),
+        ];
+        for (word, expected) in cases {
+            assert_eq!(pronounce(word), expected, "word: {}", word);
+        }
+    }
+
+    #[test]
+    fn test_lookup() {
+        let mut dict = SpellingDict::empty();
+        dict.extend_words("# コメント\n\n같이\n가치\n감사합니다\nabc\n");
+        assert_eq!(dict.lookup("가치"), vec!["같이"]);
+        assert_eq!(dict.lookup("감사함니다"), vec!["감사합니다"]);
+        // 綴りどおりの入力も発音が同じなら一致する。
+        assert_eq!(dict.lookup("감사합니다"), Vec::<String>::new());
+        assert_eq!(dict.lookup("가치 감사함니다"), vec!["같이 감사합니다", "같이 감사함니다", "가치 감사합니다"]);
+        assert_eq!(dict.lookup("abc"), Vec::<String>::new());
+    }
+
+    #[test]
+    fn test_user_dict_words() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user_dict.json");
+        std::fs::write(&path, r#"{ "entries": { "sc": "숙제 끝났어요" } }"#).unwrap();
+        let mut dict = SpellingDict::empty();
+        dict.extend_user_dict(&UserDict::load(&path));
+        assert_eq!(dict.lookup("숙쩨"), vec!["숙제"]);
+        assert_eq!(dict.lookup("끈나써요"), vec!["끝났어요"]);
+    }
+
+    #[test]
+    fn test_extend_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        std::fs::write(&path, "국물\n").unwrap();
+        let mut dict = SpellingDict::empty();
+        dict.extend_file(&path).unwrap();
+        assert_eq!(dict.lookup("궁물"), vec!["국물"]);
+        assert!(dict.extend_file(&dir.path().join("missing.txt")).is_err());
+    }
+
+    #[test]
+    fn test_corpus() {
+        let converter = HangulConverter::new();
+        let dict = SpellingDict::standard();
+        let corpus = include_str!("data/spelling_corpus.tsv");
+        for line in corpus.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+            let (input, spelling) = line.split_once('\t').unwrap();
+            let candidates = dict.candidates(&converter, input, 1);
+            assert_eq!(candidates.first().map(String::as_str), Some(spelling), "input: {}", input);
+        }
+    }
+}
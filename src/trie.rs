@@ -0,0 +1,124 @@
+//! ローマ字キーのトライ (変換エンジン用)。
+//!
+//! マッピングテーブル (`HashMap<String, u32>`) を文字単位のトライにまとめ、
+//! 変換中の検索を文字列の割り当てなしで行う。
+
+use std::collections::HashMap;
+
+/// ローマ字キー → 字母インデックスのトライ。
+pub(crate) struct KeyTrie {
+    /// ノード (0 が根)。
+    nodes: Vec<Node>,
+    /// 最長のキーの文字数。
+    max_len: usize,
+}
+
+/// トライのノード。
+struct Node {
+    /// このノードで終わるキーの値。
+    value: Option<u32>,
+    /// 子ノード (文字順にソート済み)。
+    children: Vec<(char, usize)>,
+}
+
+impl KeyTrie {
+    /// マッピングテーブルからトライを作成する。
+    pub(crate) fn new(map: &HashMap<String, u32>) -> Self {
+        let mut trie = Self {
+            nodes: vec![Node { value: None, children: Vec::new() }],
+            max_len: 0,
+        };
+        for (key, &value) in map {
+            let mut node = 0;
+            for c in key.chars() {
+                node = match trie.child(node, c) {
+                    Some(child) => child,
+                    None => trie.insert_child(node, c),
+                };
+            }
+            trie.nodes[node].value = Some(value);
+            trie.max_len = trie.max_len.max(key.chars().count());
+        }
+        trie
+    }
+
+    /// 最長のキーの文字数を返す。
+    pub(crate) fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// キーに完全一致する値を返す。
+    pub(crate) fn get(&self, key: &[char]) -> Option<u32> {
+        let mut node = 0;
+        for &c in key {
+            node = self.child(node, c)?;
+        }
+        self.nodes[node].value
+    }
+
+    /// `pos` から最長一致するキーの (値, 文字数) を返す。
+    pub(crate) fn longest_match(&self, chars: &[char], pos: usize) -> Option<(u32, usize)> {
+        let mut node = 0;
+        let mut found = None;
+        for (i, &c) in chars.iter().skip(pos).enumerate() {
+            match self.child(node, c) {
+                Some(child) => node = child,
+                None => break,
+            }
+            if let Some(value) = self.nodes[node].value {
+                found = Some((value, i + 1));
+            }
+        }
+        found
+    }
+
+    /// 子ノードを検索する。
+    fn child(&self, node: usize, c: char) -> Option<usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by_key(&c, |&(ch, _)| ch)
+            .ok()
+            .map(|i| children[i].1)
+    }
+
+    /// 子ノードを追加してそのインデックスを返す。
+    fn insert_child(&mut self, node: usize, c: char) -> usize {
+        let child = self.nodes.len();
+        self.nodes.push(Node { value: None, children: Vec::new() });
+        let children = &mut self.nodes[node].children;
+        let i = children.partition_point(|&(ch, _)| ch < c);
+        children.insert(i, (c, child));
+        child
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trie(entries: &[(&str, u32)]) -> KeyTrie {
+        KeyTrie::new(&entries.iter().map(|&(k, v)| (k.to_string(), v)).collect())
+    }
+
+    #[test]
+    fn test_longest_match() {
+        let t = trie(&[("g", 0), ("gg", 1), ("ggg", 2), ("ch", 14)]);
+        let chars: Vec<char> = "gga ch c".chars().collect();
+        assert_eq!(t.longest_match(&chars, 0), Some((1, 2)));
+        assert_eq!(t.longest_match(&chars, 1), Some((0, 1)));
+        assert_eq!(t.longest_match(&chars, 4), Some((14, 2)));
+        // 途中までしか一致しないキー (c) は値を持たない。
+        assert_eq!(t.longest_match(&chars, 7), None);
+        assert_eq!(t.longest_match(&chars, 100), None);
+        assert_eq!(t.max_len(), 3);
+    }
+
+    #[test]
+    fn test_get() {
+        let t = trie(&[("ng", 21), ("n", 4)]);
+        assert_eq!(t.get(&['n', 'g']), Some(21));
+        assert_eq!(t.get(&['n']), Some(4));
+        assert_eq!(t.get(&['g']), None);
+        assert_eq!(t.get(&[]), None);
+    }
+}
@@ -12,6 +12,16 @@
 //! │ han gug eo│  ← ローマ字入力 (小さめフォント)
 //! └──────────┘
 //! ```
+//!
+//! 候補が複数ある場合は番号付きで並べ、選択中の候補を強調表示する。
+//!
+//! ```text
+//! ┌──────────┐
+//! │ 1. 간기   │  ← 選択中 (背景色)
+//! │ 2. 강이   │
+//! │ gangi     │
+//! └──────────┘
+//! ```
 
 use std::cell::Cell;
 
@@ -60,6 +70,9 @@ const PADDING: i32 = 6;
 /// ウィンドウの枠色 (青)。
 const BORDER_COLOR: u32 = 0x00C08000;
 
+/// 選択中の候補の背景色 (薄い青)。
+const SELECTED_BG_COLOR: u32 = 0x00FFE8CC;
+
 /// 候補ウィンドウ。
 ///
 /// コンポジション中に変換結果とローマ字入力を表示する。
@@ -71,7 +84,9 @@ pub struct CandidateWindow {
 /// ウィンドウに関連付けるテキストデータ (WM_PAINTで使用)。
 ///
 /// SetWindowLongPtrで保存し、WM_PAINTで取得する。
-static HANGUL_TEXT: std::sync::Mutex<String> = std::sync::Mutex::new(String::new());
+static HANGUL_LINES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+/// 選択中の候補の行 (候補が1つの場合は None)。
+static SELECTED_LINE: std::sync::Mutex<Option<usize>> = std::sync::Mutex::new(None);
 static ROMAN_TEXT: std::sync::Mutex<String> = std::sync::Mutex::new(String::new());
 /// WM_PAINT用のDPI値。
 static PAINT_DPI: std::sync::Mutex<u32> = std::sync::Mutex::new(96);
@@ -147,10 +162,11 @@ impl CandidateWindow {
 
     /// 候補ウィンドウを表示・更新する。
     ///
-    /// `hangul`: 変換結果テキスト。
+    /// `candidates`: 変換候補 (1つの場合は番号を付けずに表示)。
+    /// `selected`: 選択中の候補のインデックス。
     /// `roman`: ローマ字入力テキスト。
     /// `x`, `y`: キャレット位置 (スクリーン座標)。
-    pub fn show(&self, hangul: &str, roman: &str, x: i32, y: i32) -> Result<()> {
+    pub fn show(&self, candidates: &[String], selected: usize, roman: &str, x: i32, y: i32) -> Result<()> {
         self.ensure_window_created()?;
 
         let hwnd = self.hwnd.get();
@@ -158,13 +174,24 @@ impl CandidateWindow {
             return Ok(());
         }
 
+        let lines: Vec<String> = if candidates.len() > 1 {
+            candidates
+                .iter()
+                .enumerate()
+                .map(|(i, c)| format!("{}. {}", i + 1, c))
+                .collect()
+        } else {
+            candidates.to_vec()
+        };
+
         // テキストを静的変数に保存 (WM_PAINTで使用)。
-        *HANGUL_TEXT.lock().unwrap() = hangul.to_string();
+        *HANGUL_LINES.lock().unwrap() = lines.clone();
+        *SELECTED_LINE.lock().unwrap() = (candidates.len() > 1).then_some(selected);
         *ROMAN_TEXT.lock().unwrap() = roman.to_string();
         *PAINT_DPI.lock().unwrap() = dpi::get_dpi_for_window(hwnd);
 
         // ウィンドウサイズを計算。
-        let (width, height) = self.calculate_size(hangul, roman);
+        let (width, height) = self.calculate_size(&lines, roman);
 
         // モニター領域内にクランプ。
         let (cx, cy) = Self::clamp_to_monitor(x, y, width, height);
@@ -202,7 +229,7 @@ impl CandidateWindow {
     }
 
     /// テキストに基づいてウィンドウサイズを計算する。
-    fn calculate_size(&self, hangul: &str, roman: &str) -> (i32, i32) {
+    fn calculate_size(&self, lines: &[String], roman: &str) -> (i32, i32) {
         let hwnd = self.hwnd.get();
         if hwnd.0.is_null() {
             return (100, 50);
@@ -217,9 +244,14 @@ impl CandidateWindow {
             // DPIスケーリングしたフォントでテキスト幅を計算。
             let hangul_font = create_font(dpi::scale(HANGUL_FONT_SIZE, window_dpi));
             let old_font = SelectObject(hdc, hangul_font);
-            let hangul_wide: Vec<u16> = hangul.encode_utf16().collect();
             let mut hangul_size = windows::Win32::Foundation::SIZE::default();
-            let _ = GetTextExtentPoint32W(hdc, &hangul_wide, &mut hangul_size);
+            for line in lines {
+                let line_wide: Vec<u16> = line.encode_utf16().collect();
+                let mut line_size = windows::Win32::Foundation::SIZE::default();
+                let _ = GetTextExtentPoint32W(hdc, &line_wide, &mut line_size);
+                hangul_size.cx = std::cmp::max(hangul_size.cx, line_size.cx);
+                hangul_size.cy += line_size.cy;
+            }
 
             // ローマ字フォントでテキスト幅を計算。
             let roman_font = create_font(dpi::scale(ROMAN_FONT_SIZE, window_dpi));
@@ -340,18 +372,33 @@ extern "system" fn candidate_window_proc(
 
                 SetBkMode(hdc, TRANSPARENT);
 
-                // 変換結果テキスト (大きめフォント、DPIスケーリング済み)。
+                // 変換候補 (大きめフォント、DPIスケーリング済み)。選択中の行は背景色で強調。
                 let hangul_font = create_font(dpi::scale(HANGUL_FONT_SIZE, paint_dpi));
                 let old_font = SelectObject(hdc, hangul_font);
                 SetTextColor(hdc, windows::Win32::Foundation::COLORREF(HANGUL_TEXT_COLOR));
-                let hangul_text = HANGUL_TEXT.lock().unwrap();
-                let hangul_wide: Vec<u16> = hangul_text.encode_utf16().collect();
-                drop(hangul_text);
-                let _ = TextOutW(hdc, scaled_padding + 1, scaled_padding + 1, &hangul_wide);
-
-                // ハングルテキストの高さを取得。
-                let mut hangul_size = windows::Win32::Foundation::SIZE::default();
-                let _ = GetTextExtentPoint32W(hdc, &hangul_wide, &mut hangul_size);
+                let lines = HANGUL_LINES.lock().unwrap().clone();
+                let selected = *SELECTED_LINE.lock().unwrap();
+                let mut line_top = scaled_padding + 1;
+                for (i, line) in lines.iter().enumerate() {
+                    let line_wide: Vec<u16> = line.encode_utf16().collect();
+                    let mut line_size = windows::Win32::Foundation::SIZE::default();
+                    let _ = GetTextExtentPoint32W(hdc, &line_wide, &mut line_size);
+                    if selected == Some(i) {
+                        let line_rc = RECT {
+                            left: rc.left + 1,
+                            top: line_top,
+                            right: rc.right - 1,
+                            bottom: line_top + line_size.cy,
+                        };
+                        let selected_brush = CreateSolidBrush(
+                            windows::Win32::Foundation::COLORREF(SELECTED_BG_COLOR),
+                        );
+                        let _ = FillRect(hdc, &line_rc, selected_brush);
+                        let _ = DeleteObject(selected_brush);
+                    }
+                    let _ = TextOutW(hdc, scaled_padding + 1, line_top, &line_wide);
+                    line_top += line_size.cy;
+                }
 
                 // ローマ字テキスト (小さめフォント、DPIスケーリング済み)。
                 let roman_font = create_font(dpi::scale(ROMAN_FONT_SIZE, paint_dpi));
@@ -363,7 +410,7 @@ extern "system" fn candidate_window_proc(
                 let _ = TextOutW(
                     hdc,
                     scaled_padding + 1,
-                    scaled_padding + 1 + hangul_size.cy + 2,
+                    line_top + 2,
                     &roman_wide,
                 );
 
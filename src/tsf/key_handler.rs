@@ -10,6 +10,7 @@ pub const VK_RETURN: u32 = 0x0D;
 pub const VK_SHIFT: u32 = 0x10;
 pub const VK_CONTROL: u32 = 0x11;
 pub const VK_MENU: u32 = 0x12;
+pub const VK_HANJA: u32 = 0x19;
 pub const VK_ESCAPE: u32 = 0x1B;
 pub const VK_SPACE: u32 = 0x20;
 pub const VK_END: u32 = 0x23;
@@ -19,23 +20,56 @@ pub const VK_UP: u32 = 0x26;
 pub const VK_RIGHT: u32 = 0x27;
 pub const VK_DOWN: u32 = 0x28;
 pub const VK_DELETE: u32 = 0x2E;
+pub const VK_F9: u32 = 0x78;
 
-/// 仮想キーコードからASCII小文字への変換。
+/// 仮想キーコードから英字への変換 (Shift考慮)。
 ///
-/// A-Z (0x41-0x5A) のキーコードを a-z に変換する。
+/// A-Z (0x41-0x5A) のキーコードを a-z、Shift押下時は A-Z に変換する。
+/// 大文字をどう扱うかはローマ字入力の設定 (case_policy) による。
 /// それ以外のキーはNoneを返す。
-pub fn vk_to_char(vk: u32) -> Option<char> {
+pub fn vk_to_char(vk: u32, shift: bool) -> Option<char> {
     match vk {
+        0x41..=0x5A if shift => Some(vk as u8 as char),
         0x41..=0x5A => Some((vk as u8 - b'A' + b'a') as char),
         _ => None,
     }
 }
 
+/// 仮想キーコードからUS配列の文字への変換 (Shift考慮)。
+///
+/// 英字・数字・記号キー (VK_OEM_*) を、Shift押下時はUS配列のShift側の文字に変換する。
+/// 3ボル式など記号キーにも字母が割り当てられた配列で使用する。
+/// それ以外のキーはNoneを返す。
+pub fn vk_to_ascii(vk: u32, shift: bool) -> Option<char> {
+    if let Some(c) = vk_to_char(vk, shift) {
+        return Some(c);
+    }
+    let (normal, shifted) = match vk {
+        0x30..=0x39 => {
+            let c = vk as u8 as char;
+            (c, b")!@#$%^&*("[(vk - 0x30) as usize] as char)
+        }
+        0xBA => (';', ':'),  // VK_OEM_1
+        0xBB => ('=', '+'),  // VK_OEM_PLUS
+        0xBC => (',', '<'),  // VK_OEM_COMMA
+        0xBD => ('-', '_'),  // VK_OEM_MINUS
+        0xBE => ('.', '>'),  // VK_OEM_PERIOD
+        0xBF => ('/', '?'),  // VK_OEM_2
+        0xC0 => ('`', '~'),  // VK_OEM_3
+        0xDB => ('[', '{'),  // VK_OEM_4
+        0xDC => ('\\', '|'), // VK_OEM_5
+        0xDD => (']', '}'),  // VK_OEM_6
+        0xDE => ('\'', '"'), // VK_OEM_7
+        _ => return None,
+    };
+    Some(if shift { shifted } else { normal })
+}
+
 /// ハングル変換で処理すべきキーか判定。
 ///
 /// ローマ字入力キー (a-z) の場合にtrueを返す。
 pub fn is_hangul_key(vk: u32) -> bool {
-    vk_to_char(vk).is_some()
+    vk_to_char(vk, false).is_some()
 }
 
 /// コンポジション中に処理すべき制御キーか判定。
@@ -45,8 +79,14 @@ pub fn is_hangul_key(vk: u32) -> bool {
 /// - Enter: コンポジション確定
 /// - Escape: コンポジションキャンセル
 /// - Space: 音節区切り
+/// - 漢字キー / F9: 漢字変換
 pub fn is_control_key(vk: u32) -> bool {
-    matches!(vk, VK_BACK | VK_RETURN | VK_SPACE | VK_ESCAPE)
+    matches!(vk, VK_BACK | VK_RETURN | VK_SPACE | VK_ESCAPE) || is_hanja_key(vk)
+}
+
+/// 漢字変換キー (漢字キーまたは F9) か判定。
+pub fn is_hanja_key(vk: u32) -> bool {
+    matches!(vk, VK_HANJA | VK_F9)
 }
 
 /// ナビゲーションキーか判定。
@@ -95,10 +135,23 @@ mod tests {
 
     #[test]
     fn test_vk_to_char() {
-        assert_eq!(vk_to_char(0x41), Some('a'));
-        assert_eq!(vk_to_char(0x5A), Some('z'));
-        assert_eq!(vk_to_char(0x20), None); // Space
-        assert_eq!(vk_to_char(0x08), None); // Backspace
+        assert_eq!(vk_to_char(0x41, false), Some('a'));
+        assert_eq!(vk_to_char(0x5A, false), Some('z'));
+        assert_eq!(vk_to_char(0x47, true), Some('G'));
+        assert_eq!(vk_to_char(0x20, false), None); // Space
+        assert_eq!(vk_to_char(0x08, true), None); // Backspace
+    }
+
+    #[test]
+    fn test_vk_to_ascii() {
+        assert_eq!(vk_to_ascii(0x41, false), Some('a'));
+        assert_eq!(vk_to_ascii(0x41, true), Some('A'));
+        assert_eq!(vk_to_ascii(0x30, false), Some('0'));
+        assert_eq!(vk_to_ascii(0x31, true), Some('!'));
+        assert_eq!(vk_to_ascii(0x39, true), Some('('));
+        assert_eq!(vk_to_ascii(0xBA, false), Some(';'));
+        assert_eq!(vk_to_ascii(0xDE, true), Some('"'));
+        assert_eq!(vk_to_ascii(0x20, false), None); // Space
     }
 
     #[test]
@@ -115,6 +168,8 @@ mod tests {
         assert!(is_control_key(VK_RETURN));
         assert!(is_control_key(VK_SPACE));
         assert!(is_control_key(VK_ESCAPE));
+        assert!(is_control_key(VK_HANJA));
+        assert!(is_control_key(VK_F9));
         assert!(!is_control_key(VK_TAB));
         assert!(!is_control_key(0x41));
     }
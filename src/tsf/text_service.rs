@@ -23,15 +23,25 @@ use windows::Win32::UI::TextServices::{
     TF_ES_READWRITE, TF_ES_SYNC,
 };
 
+use crate::choseong::{self, ChoseongDict};
 use crate::com::dll_module;
 use crate::config::Config;
-use crate::hangul::HangulConverter;
+use crate::hangul::{Composer, ConvertOptions, HangulConverter, InputScheme};
+use crate::hanja::HanjaDict;
+use crate::palette::SymbolPalette;
+use crate::spelling::SpellingDict;
 use crate::tsf::candidate_window::CandidateWindow;
 use crate::tsf::edit_session::{CaretPos, EditAction, EditSession};
 use crate::tsf::key_handler;
 use crate::tsf::tray_icon::{TrayAction, TrayIcon};
 use crate::user_dict::UserDict;
 
+/// 候補ウィンドウの1ページに表示する候補の最大数 (数字キー 1〜9 で選択)。
+const MAX_CANDIDATES: usize = 9;
+
+/// 初声検索の候補を出す子音の最小数 (子音1つ + 漢字キーは記号パレット)。
+const MIN_CHOSEONG_PATTERN: usize = 2;
+
 /// Chamsae TextService。
 ///
 /// TSFのテキスト入力プロセッサとして機能する。
@@ -42,20 +52,32 @@ pub struct TextService {
     thread_mgr: RefCell<Option<ITfThreadMgr>>,
     /// TSFから割り当てられたクライアントID。
     client_id: Cell<u32>,
-    /// ローマ字入力バッファ。
-    roman_buffer: RefCell<String>,
+    /// ローマ字入力の合成器 (入力バッファと変換結果を保持)。
+    composer: RefCell<Composer>,
     /// アクティブなコンポジション (TextServiceとEditSessionで共有)。
     composition: Arc<Mutex<Option<ITfComposition>>>,
     /// キャレット位置 (EditSessionが更新)。
     caret_pos: Arc<Mutex<CaretPos>>,
-    /// ハングル変換器。
-    converter: HangulConverter,
     /// IME設定。
     config: RefCell<Config>,
     /// ユーザー辞書。
     user_dict: RefCell<UserDict>,
+    /// 標準の綴りの単語リスト (spelling_candidates が有効な場合のみ)。
+    spelling: RefCell<Option<SpellingDict>>,
+    /// 初声検索の単語リスト (choseong_search が有効な場合のみ)。
+    choseong: RefCell<Option<ChoseongDict>>,
+    /// 漢字辞書 (標準の漢字辞書 + hanja_dict_path)。
+    hanja: RefCell<HanjaDict>,
+    /// 記号パレット (子音ごとの記号表 + 絵文字のショートコード表)。
+    palette: RefCell<SymbolPalette>,
     /// 候補ウィンドウ。
     candidate_window: CandidateWindow,
+    /// 現在の変換候補 (先頭がユーザー辞書または合成器の変換結果)。
+    candidates: RefCell<Vec<String>>,
+    /// 候補ウィンドウに表示する候補の文字列 (漢字の意味付きなど)。空なら候補をそのまま表示する。
+    candidate_labels: RefCell<Vec<String>>,
+    /// 選択中の候補のインデックス。
+    selected: Cell<usize>,
     /// システムトレイアイコン。
     tray_icon: TrayIcon,
     /// IME有効状態。falseの場合はすべてのキーをパススルーする。
@@ -68,21 +90,45 @@ impl TextService {
         dll_module::increment_object_count();
         let config = Config::load_from_dll();
         let user_dict = Self::load_user_dict(&config);
+        let spelling = Self::load_spelling(&config, &user_dict);
+        let choseong = Self::load_choseong(&config, &user_dict);
+        let hanja = Self::load_hanja(&config);
+        let palette = Self::load_palette(&config);
         Self {
             thread_mgr: RefCell::new(None),
             client_id: Cell::new(0),
-            roman_buffer: RefCell::new(String::new()),
+            composer: RefCell::new(Self::create_composer(&config)),
             composition: Arc::new(Mutex::new(None)),
             caret_pos: Arc::new(Mutex::new(CaretPos::default())),
-            converter: HangulConverter::new(),
             config: RefCell::new(config),
             user_dict: RefCell::new(user_dict),
+            spelling: RefCell::new(spelling),
+            choseong: RefCell::new(choseong),
+            hanja: RefCell::new(hanja),
+            palette: RefCell::new(palette),
             candidate_window: CandidateWindow::new(),
+            candidates: RefCell::new(Vec::new()),
+            candidate_labels: RefCell::new(Vec::new()),
+            selected: Cell::new(0),
             tray_icon: TrayIcon::new(),
             enabled: Cell::new(true),
         }
     }
 
+    /// 設定の入力方式・変換オプション・ローマ字テーブルから合成器を作成する。
+    fn create_composer(config: &Config) -> Composer {
+        if config.input_scheme.layout().is_some() {
+            return Composer::with_scheme(config.input_scheme);
+        }
+        // 標準テーブルの変換器は他の TextService と共有する。
+        let Some(ref path_str) = config.romaja_table_path else {
+            return Composer::with_converter(HangulConverter::shared(config.input_scheme, config.convert_options));
+        };
+        let tables = config.input_scheme.romaja_tables().unwrap_or_default();
+        let tables = tables.with_file(std::path::Path::new(path_str));
+        Composer::with_converter(HangulConverter::with_tables(tables, config.convert_options))
+    }
+
     /// ユーザー辞書を読み込む。
     fn load_user_dict(config: &Config) -> UserDict {
         if let Some(ref path_str) = config.user_dict_path {
@@ -102,6 +148,66 @@ impl TextService {
             }
         }
     }
+
+    /// 標準の綴りの単語リストを読み込む (標準の単語リスト + 設定の単語リスト + ユーザー辞書の語)。
+    ///
+    /// spelling_candidates が無効ならNone。設定の単語リストが読めない場合は標準の単語リストで続行する。
+    fn load_spelling(config: &Config, user_dict: &UserDict) -> Option<SpellingDict> {
+        if !config.spelling_candidates {
+            return None;
+        }
+        let mut spelling = SpellingDict::standard();
+        if let Some(ref path_str) = config.spelling_word_list_path {
+            if let Err(e) = spelling.extend_file(std::path::Path::new(path_str)) {
+                log::warn!("Failed to load spelling word list: {}", e);
+            }
+        }
+        spelling.extend_user_dict(user_dict);
+        Some(spelling)
+    }
+
+    /// 初声検索の単語リストを読み込む (標準の単語リスト + 設定の単語リスト + ユーザー辞書の語)。
+    ///
+    /// choseong_search が無効ならNone。設定の単語リストが読めない場合は標準の単語リストで続行する。
+    fn load_choseong(config: &Config, user_dict: &UserDict) -> Option<ChoseongDict> {
+        if !config.choseong_search {
+            return None;
+        }
+        let mut choseong = ChoseongDict::standard();
+        if let Some(ref path_str) = config.choseong_word_list_path {
+            if let Err(e) = choseong.extend_file(std::path::Path::new(path_str)) {
+                log::warn!("Failed to load choseong word list: {}", e);
+            }
+        }
+        choseong.extend_user_dict(user_dict);
+        Some(choseong)
+    }
+
+    /// 漢字辞書を読み込む (標準の漢字辞書 + 設定の漢字辞書)。
+    ///
+    /// 設定の漢字辞書が読めない場合は標準の漢字辞書で続行する。
+    fn load_hanja(config: &Config) -> HanjaDict {
+        let mut hanja = HanjaDict::standard();
+        if let Some(ref path_str) = config.hanja_dict_path {
+            if let Err(e) = hanja.extend_file(std::path::Path::new(path_str)) {
+                log::warn!("Failed to load hanja dictionary: {}", e);
+            }
+        }
+        hanja
+    }
+
+    /// 記号パレットを読み込む (標準の記号表・絵文字 + 設定の絵文字のショートコード表)。
+    ///
+    /// 設定の絵文字のショートコード表が読めない場合は標準の表で続行する。
+    fn load_palette(config: &Config) -> SymbolPalette {
+        let mut palette = SymbolPalette::standard();
+        if let Some(ref path_str) = config.emoji_table_path {
+            if let Err(e) = palette.extend_emoji_file(std::path::Path::new(path_str)) {
+                log::warn!("Failed to load emoji table: {}", e);
+            }
+        }
+        palette
+    }
 }
 
 impl Drop for TextService {
@@ -137,7 +243,7 @@ impl ITfTextInputProcessor_Impl for TextService_Impl {
         self.tray_icon.destroy();
 
         // 状態をクリア。
-        self.roman_buffer.borrow_mut().clear();
+        self.composer.borrow_mut().clear();
         *self.composition.lock().unwrap() = None;
         *self.thread_mgr.borrow_mut() = None;
         self.client_id.set(0);
@@ -184,9 +290,9 @@ impl ITfKeyEventSink_Impl for TextService_Impl {
     fn OnSetFocus(&self, fforeground: BOOL) -> Result<()> {
         if fforeground == FALSE {
             // フォーカス喪失時: バッファをクリアして候補ウィンドウを非表示。
-            if !self.roman_buffer.borrow().is_empty() {
+            if !self.composer.borrow().is_empty() {
                 log::info!("Focus lost: clearing composition");
-                self.roman_buffer.borrow_mut().clear();
+                self.composer.borrow_mut().clear();
                 *self.composition.lock().unwrap() = None;
             }
             self.candidate_window.hide();
@@ -215,22 +321,22 @@ impl ITfKeyEventSink_Impl for TextService_Impl {
         // Ctrl/Alt押下中はハングルキーを捕捉しない。
         if self.is_modifier_held() {
             // バッファ非空なら自動確定用に横取り。
-            if !self.roman_buffer.borrow().is_empty() {
+            if !self.composer.borrow().is_empty() {
                 return Ok(TRUE);
             }
             return Ok(FALSE);
         }
 
-        if key_handler::is_hangul_key(vk) {
+        if self.input_key(vk).is_some() {
             return Ok(TRUE);
         }
 
-        if key_handler::is_control_key(vk) && !self.roman_buffer.borrow().is_empty() {
+        if key_handler::is_control_key(vk) && !self.composer.borrow().is_empty() {
             return Ok(TRUE);
         }
 
         // バッファ非空時、未対応キーも自動確定用に横取り。
-        if !self.roman_buffer.borrow().is_empty() {
+        if !self.composer.borrow().is_empty() {
             return Ok(TRUE);
         }
 
@@ -264,9 +370,9 @@ impl ITfKeyEventSink_Impl for TextService_Impl {
         // トグルキー (Shift+Space) でIMEのON/OFFを切り替え。
         if self.is_toggle_key(vk) {
             // コンポジション中なら確定してからトグル。
-            if !self.roman_buffer.borrow().is_empty() {
+            if !self.composer.borrow().is_empty() {
                 self.request_edit_session(context, EditAction::Commit)?;
-                self.roman_buffer.borrow_mut().clear();
+                self.composer.borrow_mut().clear();
                 self.candidate_window.hide();
             }
             let new_state = !self.enabled.get();
@@ -284,27 +390,41 @@ impl ITfKeyEventSink_Impl for TextService_Impl {
         // Ctrl/Alt押下中はハングルキーを処理しない。
         if self.is_modifier_held() {
             // バッファ非空なら自動確定してパススルー。
-            if !self.roman_buffer.borrow().is_empty() {
+            if !self.composer.borrow().is_empty() {
                 self.request_edit_session(context, EditAction::Commit)?;
-                self.roman_buffer.borrow_mut().clear();
+                self.composer.borrow_mut().clear();
                 self.candidate_window.hide();
             }
             return Ok(FALSE);
         }
 
-        // ローマ字キー → バッファに追加してコンポジション更新。
-        if let Some(ch) = key_handler::vk_to_char(vk) {
-            self.roman_buffer.borrow_mut().push(ch);
+        // 漢字・記号の候補の表示中は、数字キーを入力キーより先に候補の選択とする
+        // (3ボル式では数字キーも字母のキー)。
+        if !self.candidate_labels.borrow().is_empty() {
+            if let Some(index) = self.digit_candidate(vk) {
+                self.commit_candidate(context, index)?;
+                return Ok(TRUE);
+            }
+        }
+
+        // 既定以外の候補を選択中なら、次の入力キー・スペースの前に選択中の候補を確定する。
+        if self.has_selection() && (vk == key_handler::VK_SPACE || self.input_key(vk).is_some()) {
+            self.commit_candidate(context, self.selected.get())?;
+        }
+
+        // 入力キー → バッファに追加してコンポジション更新。
+        if let Some(ch) = self.input_key(vk) {
+            self.composer.borrow_mut().push(ch);
             self.update_composition(context)?;
             return Ok(TRUE);
         }
 
         // 制御キーの処理 (バッファが空でない場合のみ)。
-        if !self.roman_buffer.borrow().is_empty() {
+        if !self.composer.borrow().is_empty() {
             match vk {
                 key_handler::VK_BACK => {
-                    self.roman_buffer.borrow_mut().pop();
-                    if self.roman_buffer.borrow().is_empty() {
+                    self.composer.borrow_mut().pop();
+                    if self.composer.borrow().is_empty() {
                         self.request_edit_session(context, EditAction::Cancel)?;
                         self.candidate_window.hide();
                     } else {
@@ -313,30 +433,54 @@ impl ITfKeyEventSink_Impl for TextService_Impl {
                 }
                 key_handler::VK_RETURN => {
                     self.request_edit_session(context, EditAction::Commit)?;
-                    self.roman_buffer.borrow_mut().clear();
+                    self.composer.borrow_mut().clear();
                     self.candidate_window.hide();
                 }
                 key_handler::VK_ESCAPE => {
                     self.request_edit_session(context, EditAction::Cancel)?;
-                    self.roman_buffer.borrow_mut().clear();
+                    self.composer.borrow_mut().clear();
                     self.candidate_window.hide();
                 }
+                // 上下矢印 → 候補の選択を移動 (候補が複数ある場合のみ)。
+                key_handler::VK_UP | key_handler::VK_DOWN if self.candidates.borrow().len() > 1 => {
+                    let count = self.candidates.borrow().len();
+                    let selected = self.selected.get();
+                    self.selected.set(if vk == key_handler::VK_DOWN {
+                        (selected + 1) % count
+                    } else {
+                        (selected + count - 1) % count
+                    });
+                    self.show_selected_candidate(context)?;
+                }
+                // 数字キー 1〜9 → 表示中のページの候補を選択して確定 (候補が複数ある場合のみ)。
+                // 数字のみの入力中は入力キーとして先に処理される。
+                vk @ 0x31..=0x39 if self.digit_candidate(vk).is_some() => {
+                    if let Some(index) = self.digit_candidate(vk) {
+                        self.commit_candidate(context, index)?;
+                    }
+                }
+                // 漢字キー / F9 → 子音のみなら記号パレット、それ以外は漢字・絵文字の候補を表示
+                // (候補がなければ何もしない)。
+                vk if key_handler::is_hanja_key(vk) => {
+                    self.show_hanja_candidates(context)?;
+                }
+                // スペース → 音節区切り (区切りが記号の場合は実際のスペース)。
                 key_handler::VK_SPACE => {
-                    self.roman_buffer.borrow_mut().push(' ');
+                    self.composer.borrow_mut().push(' ');
                     self.update_composition(context)?;
                 }
                 // ナビゲーションキー → 自動確定してパススルー。
                 vk if key_handler::is_navigation_key(vk) => {
                     log::info!("Navigation key (vk=0x{:02X}): auto-commit and passthrough", vk);
                     self.request_edit_session(context, EditAction::Commit)?;
-                    self.roman_buffer.borrow_mut().clear();
+                    self.composer.borrow_mut().clear();
                     self.candidate_window.hide();
                     return Ok(FALSE);
                 }
                 // 未対応キー → 自動確定してパススルー。
                 _ => {
                     self.request_edit_session(context, EditAction::Commit)?;
-                    self.roman_buffer.borrow_mut().clear();
+                    self.composer.borrow_mut().clear();
                     self.candidate_window.hide();
                     return Ok(FALSE);
                 }
@@ -373,7 +517,7 @@ impl ITfCompositionSink_Impl for TextService_Impl {
         _ecwrite: u32,
         _pcomposition: Option<&ITfComposition>,
     ) -> Result<()> {
-        self.roman_buffer.borrow_mut().clear();
+        self.composer.borrow_mut().clear();
         *self.composition.lock().unwrap() = None;
         self.candidate_window.hide();
         Ok(())
@@ -396,6 +540,22 @@ impl TextService_Impl {
         shift_held == tk.shift && ctrl_held == tk.ctrl && alt_held == tk.alt
     }
 
+    /// 入力方式で処理するキーなら、対応するUS配列の文字を返す。
+    ///
+    /// Shift押下中は大文字・Shift側の記号 (字母配列入力で濃音・終声などを選択。
+    /// ローマ字入力では設定の大文字の扱い (case_policy) に従う)。
+    /// 記号入力 (symbol_mode) が有効なら記号の綴りに使う記号キーも処理する。
+    /// ローマ字入力の数字キーは入力が空か数字のみの間だけ処理する (数詞の候補を表示)。
+    fn input_key(&self, vk: u32) -> Option<char> {
+        let ch = key_handler::vk_to_ascii(vk, self.is_shift_held())?;
+        self.composer.borrow().is_input_key(ch).then_some(ch)
+    }
+
+    /// Shiftが押下されているか判定する。
+    fn is_shift_held(&self) -> bool {
+        unsafe { GetKeyState(key_handler::VK_SHIFT as i32) < 0 }
+    }
+
     /// Ctrl/Altが押下されているか判定する。
     fn is_modifier_held(&self) -> bool {
         unsafe {
@@ -404,27 +564,151 @@ impl TextService_Impl {
         }
     }
 
-    /// バッファの内容をハングルに変換してコンポジションを更新する。
+    /// 合成器の内容で変換候補とコンポジションを更新する。
     ///
-    /// ユーザー辞書に完全一致するエントリがあればそちらを使用し、
-    /// なければハングル変換エンジンで変換する。
-    /// 変換後、候補ウィンドウにテキストを表示する。
+    /// ユーザー辞書に完全一致するエントリがあればその値 (テンプレートは展開したもの) を先頭の候補とし、
+    /// 続けて合成器の変換結果 (確定済み + 入力中) と音節の区切り方が異なる候補を並べる。
+    /// 区切り方を変えるのは入力の末尾のみで、それより前は合成器の確定済みの変換結果を使うため、
+    /// 長いコンポジションでもキー入力ごとに入力全体を変換し直さない。
+    /// spelling_candidates が有効なら、変換結果の直後に標準の綴りの候補を入れる。
+    /// choseong_search が有効で入力が子音だけなら、変換結果の直後に初声検索の候補を入れる。
+    /// 先頭の候補を選択した状態でコンポジションと候補ウィンドウを更新する。
     fn update_composition(&self, context: &ITfContext) -> Result<()> {
-        let buffer = self.roman_buffer.borrow();
-        let user_dict = self.user_dict.borrow();
-        let converted = if let Some(dict_value) = user_dict.lookup(&buffer) {
-            dict_value.to_string()
-        } else {
-            self.converter.convert(&buffer)
+        let composer = self.composer.borrow();
+        let roman_display = composer.input();
+        let mut candidates: Vec<String> = composer
+            .candidates(MAX_CANDIDATES)
+            .into_iter()
+            .map(|c| c.text)
+            .collect();
+        drop(composer);
+        if let Some(ref spelling) = *self.spelling.borrow() {
+            let mut spellings: Vec<String> = Vec::new();
+            for text in candidates.iter().flat_map(|c| spelling.lookup(c)) {
+                if !candidates.contains(&text) && !spellings.contains(&text) {
+                    spellings.push(text);
+                }
+            }
+            let at = candidates.len().min(1);
+            candidates.splice(at..at, spellings);
+            candidates.truncate(MAX_CANDIDATES);
+        }
+        // 初声検索の子音とテンプレートの {キー} は入力方式によらずローマ字として変換する。
+        let converter = HangulConverter::shared(InputScheme::Romaja, ConvertOptions::default());
+        if let Some(ref dict) = *self.choseong.borrow() {
+            // 変換結果は子音のローマ字 (hg) または互換字母 (ㅎㄱ) のまま。
+            let pattern = candidates.first().and_then(|text| choseong::pattern(&converter, text));
+            if let Some(pattern) = pattern.filter(|p| p.len() >= MIN_CHOSEONG_PATTERN) {
+                let words: Vec<String> = dict
+                    .search(&pattern, MAX_CANDIDATES)
+                    .into_iter()
+                    .filter(|w| !candidates.contains(w))
+                    .collect();
+                let at = candidates.len().min(1);
+                candidates.splice(at..at, words);
+                candidates.truncate(MAX_CANDIDATES);
+            }
+        }
+        if let Some(dict_value) = self.user_dict.borrow().resolve(&roman_display.to_lowercase(), &converter) {
+            candidates.retain(|c| *c != dict_value);
+            candidates.insert(0, dict_value);
+            candidates.truncate(MAX_CANDIDATES);
+        }
+        *self.candidates.borrow_mut() = candidates;
+        self.candidate_labels.borrow_mut().clear();
+        self.selected.set(0);
+        self.show_selected_candidate(context)
+    }
+
+    /// 漢字キーの候補 (記号パレットまたは漢字・絵文字) に置き換えて表示する。
+    ///
+    /// 入力が子音1つ (ㅁ, m) なら記号パレットのその子音の記号をすべて候補とする (ページ送りは↑↓キー)。
+    /// それ以外は選択中の候補の先頭から一致する長い読みの順に漢字に置き換えた文字列と、
+    /// 入力 (ローマ字) をショートコードとする絵文字を候補とする。
+    /// 候補ウィンドウには漢字と意味、絵文字とショートコードを表示する。
+    fn show_hanja_candidates(&self, context: &ITfContext) -> Result<()> {
+        let composer = self.composer.borrow();
+        let consonant = composer.consonant();
+        let input = composer.input();
+        drop(composer);
+
+        let candidates: Vec<(String, String)> = match consonant {
+            Some(consonant) => self
+                .palette
+                .borrow()
+                .candidates(&consonant.to_string(), usize::MAX)
+                .into_iter()
+                .map(|c| (c.text, c.label))
+                .collect(),
+            None => {
+                let Some(text) = self.candidates.borrow().get(self.selected.get()).cloned() else {
+                    return Ok(());
+                };
+                let hanja = self.hanja.borrow().candidates(&text, MAX_CANDIDATES);
+                let emoji = self.palette.borrow().emoji_candidates(&input, MAX_CANDIDATES);
+                hanja
+                    .into_iter()
+                    .map(|c| {
+                        let label = c.label();
+                        (c.text, label)
+                    })
+                    .chain(emoji.into_iter().map(|c| (c.text, c.label)))
+                    .collect()
+            }
         };
-        drop(user_dict);
-        let roman_display = buffer.clone();
+        if candidates.is_empty() {
+            return Ok(());
+        }
+        let (texts, labels): (Vec<String>, Vec<String>) = candidates.into_iter().unzip();
+        *self.candidates.borrow_mut() = texts;
+        *self.candidate_labels.borrow_mut() = labels;
+        self.selected.set(0);
+        self.show_selected_candidate(context)
+    }
+
+    /// 既定の変換結果以外の候補 (↑↓キーで選んだ候補、漢字・記号の候補) を選択中ならtrue。
+    fn has_selection(&self) -> bool {
+        !self.composer.borrow().is_empty() && (self.selected.get() != 0 || !self.candidate_labels.borrow().is_empty())
+    }
+
+    /// 数字キー 1〜9 で選べる表示中のページの候補のインデックス。候補が1つ以下ならNone。
+    fn digit_candidate(&self, vk: u32) -> Option<usize> {
+        let count = self.candidates.borrow().len();
+        let index = self.page_start() + vk.checked_sub(0x31).filter(|&d| d < 9)? as usize;
+        (count > 1 && index < count).then_some(index)
+    }
+
+    /// 候補を選択して確定する。
+    fn commit_candidate(&self, context: &ITfContext, index: usize) -> Result<()> {
+        self.selected.set(index);
+        self.show_selected_candidate(context)?;
+        self.request_edit_session(context, EditAction::Commit)?;
+        self.composer.borrow_mut().clear();
+        self.candidate_window.hide();
+        Ok(())
+    }
+
+    /// 選択中の候補を含むページの先頭のインデックス。
+    fn page_start(&self) -> usize {
+        self.selected.get() / MAX_CANDIDATES * MAX_CANDIDATES
+    }
+
+    /// 選択中の候補でコンポジションを更新し、候補ウィンドウを表示する。
+    fn show_selected_candidate(&self, context: &ITfContext) -> Result<()> {
+        let candidates = self.candidates.borrow().clone();
+        let selected = self.selected.get();
+        let converted = candidates.get(selected).cloned().unwrap_or_default();
         let text: Vec<u16> = converted.encode_utf16().collect();
         self.request_edit_session(context, EditAction::Update(text))?;
 
         // 候補ウィンドウを表示。
+        let roman_display = self.composer.borrow().input();
         let pos = self.caret_pos.lock().unwrap().clone();
-        let _ = self.candidate_window.show(&converted, &roman_display, pos.x, pos.y);
+        let labels = self.candidate_labels.borrow();
+        let lines = if labels.is_empty() { &candidates } else { &*labels };
+        let page_start = self.page_start();
+        let page = &lines[page_start.min(lines.len())..(page_start + MAX_CANDIDATES).min(lines.len())];
+        let _ = self.candidate_window.show(page, selected - page_start, &roman_display, pos.x, pos.y);
 
         Ok(())
     }
@@ -434,6 +718,11 @@ impl TextService_Impl {
         log::info!("Reloading config and user dictionary");
         let new_config = Config::load_from_dll();
         let new_dict = TextService::load_user_dict(&new_config);
+        *self.spelling.borrow_mut() = TextService::load_spelling(&new_config, &new_dict);
+        *self.choseong.borrow_mut() = TextService::load_choseong(&new_config, &new_dict);
+        *self.hanja.borrow_mut() = TextService::load_hanja(&new_config);
+        *self.palette.borrow_mut() = TextService::load_palette(&new_config);
+        *self.composer.borrow_mut() = TextService::create_composer(&new_config);
         *self.config.borrow_mut() = new_config;
         *self.user_dict.borrow_mut() = new_dict;
         log::info!("Config and user dictionary reloaded");
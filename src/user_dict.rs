@@ -4,15 +4,23 @@
 //! ユーザー定義の変換エントリを提供する。
 //! 変換エンジンで処理する前にユーザー辞書を参照し、
 //! 完全一致すれば辞書の値を使用する。
+//!
+//! `entries` の値はそのまま使う。`templates` の値はテンプレートとして展開する ([`UserDict::expand`])。
+//! `{キー}` は `entries` の値 (なければキーのローマ字の変換結果) に置き換え、助詞の表記 (은/는 など) は直前の文字に合う形にする。
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::Path;
 
+use crate::hangul::HangulConverter;
+use crate::josa;
+
 /// ユーザー辞書JSONのルート構造。
 #[derive(Serialize, Deserialize)]
 struct UserDictJson {
     entries: HashMap<String, String>,
+    #[serde(default)]
+    templates: HashMap<String, String>,
 }
 
 /// ユーザー辞書。
@@ -21,6 +29,8 @@ struct UserDictJson {
 /// キー (ローマ字) に対する変換結果を返す。
 pub struct UserDict {
     entries: HashMap<String, String>,
+    /// テンプレート (キー → 展開前の値)。
+    templates: HashMap<String, String>,
 }
 
 impl UserDict {
@@ -40,6 +50,7 @@ impl UserDict {
 
         Self {
             entries: json.entries,
+            templates: json.templates,
         }
     }
 
@@ -47,6 +58,7 @@ impl UserDict {
     pub fn empty() -> Self {
         Self {
             entries: HashMap::new(),
+            templates: HashMap::new(),
         }
     }
 
@@ -54,6 +66,31 @@ impl UserDict {
     pub fn lookup(&self, key: &str) -> Option<&str> {
         self.entries.get(key).map(|s| s.as_str())
     }
+
+    /// キーに一致するエントリの値、なければテンプレートを展開した結果を返す (完全一致)。
+    pub fn resolve(&self, key: &str, converter: &HangulConverter) -> Option<String> {
+        self.lookup(key).map(str::to_string).or_else(|| self.expand(key, converter))
+    }
+
+    /// キーに一致するテンプレートを展開する (完全一致)。
+    ///
+    /// `{キー}` はエントリの値、なければキーを `converter` で変換した結果に置き換え、
+    /// 助詞の表記を直前の文字に合う形にする (`{name}은/는` → 김철수는)。
+    pub fn expand(&self, key: &str, converter: &HangulConverter) -> Option<String> {
+        self.templates.get(key).map(|template| self.expand_template(template, converter))
+    }
+
+    /// テンプレートを展開する。`{キー}` と助詞の表記の扱いは [`UserDict::expand`] と同じ。
+    pub fn expand_template(&self, template: &str, converter: &HangulConverter) -> String {
+        josa::expand(template, |name| {
+            self.lookup(name).map_or_else(|| converter.convert(name), str::to_string)
+        })
+    }
+
+    /// すべてのエントリの値 (変換結果) を返す (テンプレートは含まない。順序は不定)。
+    pub fn values(&self) -> impl Iterator<Item = &str> {
+        self.entries.values().map(String::as_str)
+    }
 }
 
 #[cfg(test)]
@@ -81,6 +118,33 @@ mod tests {
         assert_eq!(dict.lookup("nonexistent"), None);
     }
 
+    #[test]
+    fn test_expand() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("user_dict.json");
+        let json = r#"{
+            "entries": {
+                "name": "김철수",
+                "plain": "{name}은/는"
+            },
+            "templates": {
+                "intro": "{name}은/는 {seoul}에 살고 {busan}(으)로 가요"
+            }
+        }"#;
+        fs::write(&path, json).unwrap();
+
+        let dict = UserDict::load(&path);
+        let converter = HangulConverter::new();
+        assert_eq!(dict.expand("intro", &converter).as_deref(), Some("김철수는 서울에 살고 부산으로 가요"));
+        assert_eq!(dict.resolve("intro", &converter).as_deref(), Some("김철수는 서울에 살고 부산으로 가요"));
+        // エントリの値はテンプレートとして展開しない。
+        assert_eq!(dict.lookup("plain"), Some("{name}은/는"));
+        assert_eq!(dict.resolve("plain", &converter).as_deref(), Some("{name}은/는"));
+        assert_eq!(dict.expand("plain", &converter), None);
+        assert_eq!(dict.resolve("nonexistent", &converter), None);
+        assert_eq!(dict.expand_template("{name}과/와 {na}", &converter), "김철수와 나");
+    }
+
     #[test]
     fn test_load_missing_file() {
         let path = Path::new("/tmp/nonexistent_user_dict.json");